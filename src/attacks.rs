@@ -0,0 +1,74 @@
+use crate::bitboard::Bitboard;
+use crate::board::Color;
+use crate::constants::*;
+
+/// Squares attacked by a knight on the given square
+pub fn knight_attacks(square: u8) -> Bitboard {
+    Bitboard::from_u64(KNIGHT_MOVES[square as usize])
+}
+
+/// Squares attacked by a king on the given square
+pub fn king_attacks(square: u8) -> Bitboard {
+    Bitboard::from_u64(KING_MOVES[square as usize])
+}
+
+/// Squares attacked by a pawn of the given color on the given square
+pub fn pawn_attacks(color: Color, square: u8) -> Bitboard {
+    match color {
+        Color::White => Bitboard::from_u64(WHITE_PAWN_ATTACKS[square as usize]),
+        Color::Black => Bitboard::from_u64(BLACK_PAWN_ATTACKS[square as usize]),
+    }
+}
+
+/// Squares attacked by a rook on the given square, stopping at the first occupied square in each direction
+pub fn rook_attacks(square: u8, occupied: Bitboard) -> Bitboard {
+    let sq = square as usize;
+    let occupied = occupied.as_u64();
+
+    Bitboard::from_u64(
+        positive_ray_attacks(&NORTH_RAY, sq, occupied)
+            | positive_ray_attacks(&EAST_RAY, sq, occupied)
+            | negative_ray_attacks(&SOUTH_RAY, sq, occupied)
+            | negative_ray_attacks(&WEST_RAY, sq, occupied),
+    )
+}
+
+/// Squares attacked by a bishop on the given square, stopping at the first occupied square in each direction
+pub fn bishop_attacks(square: u8, occupied: Bitboard) -> Bitboard {
+    let sq = square as usize;
+    let occupied = occupied.as_u64();
+
+    Bitboard::from_u64(
+        positive_ray_attacks(&NORTH_EAST_RAY, sq, occupied)
+            | positive_ray_attacks(&NORTH_WEST_RAY, sq, occupied)
+            | negative_ray_attacks(&SOUTH_EAST_RAY, sq, occupied)
+            | negative_ray_attacks(&SOUTH_WEST_RAY, sq, occupied),
+    )
+}
+
+/// Squares attacked by a queen on the given square
+pub fn queen_attacks(square: u8, occupied: Bitboard) -> Bitboard {
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
+
+// Rays pointing towards higher square indices are cut at their lowest blocker
+fn positive_ray_attacks(rays: &[u64; 64], square: usize, occupied: u64) -> u64 {
+    let ray = rays[square];
+    let blockers = ray & occupied;
+    if blockers == 0 {
+        ray
+    } else {
+        ray ^ rays[blockers.trailing_zeros() as usize]
+    }
+}
+
+// Rays pointing towards lower square indices are cut at their highest blocker
+fn negative_ray_attacks(rays: &[u64; 64], square: usize, occupied: u64) -> u64 {
+    let ray = rays[square];
+    let blockers = ray & occupied;
+    if blockers == 0 {
+        ray
+    } else {
+        ray ^ rays[63 - blockers.leading_zeros() as usize]
+    }
+}
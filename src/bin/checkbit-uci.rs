@@ -0,0 +1,5 @@
+use std::io;
+
+fn main() -> io::Result<()> {
+    checkbit::uci::run(io::stdin().lock(), io::stdout())
+}
@@ -1,3 +1,4 @@
+use crate::board::Color;
 use crate::constants::{FILE_A, FILE_H, RANK_1, RANK_8};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -42,6 +43,16 @@ impl Bitboard {
         (self.0 & (1u64 << square)) != 0
     }
 
+    /// Returns true if no bits are set
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns an iterator over the indices of the set bits, from least to most significant
+    pub fn squares(&self) -> Squares {
+        Squares(self.0)
+    }
+
     /// Returns the number of set bits (population count)
     pub fn pop_count(&self) -> u32 {
         self.0.count_ones()
@@ -100,6 +111,132 @@ impl Bitboard {
             Some(Bitboard(self.0 >> 1))
         }
     }
+
+    /// Returns the bitboard mirrored top to bottom, so a1 maps to a8
+    pub fn flip_vertical(&self) -> Bitboard {
+        Bitboard(self.0.swap_bytes())
+    }
+
+    /// Returns the bitboard mirrored left to right, so a1 maps to h1
+    pub fn mirror_horizontal(&self) -> Bitboard {
+        const K1: u64 = 0x5555_5555_5555_5555;
+        const K2: u64 = 0x3333_3333_3333_3333;
+        const K4: u64 = 0x0F0F_0F0F_0F0F_0F0F;
+        let mut bits = self.0;
+        bits = ((bits >> 1) & K1) | ((bits & K1) << 1);
+        bits = ((bits >> 2) & K2) | ((bits & K2) << 2);
+        bits = ((bits >> 4) & K4) | ((bits & K4) << 4);
+        Bitboard(bits)
+    }
+
+    /// Returns the bitboard mirrored along the a1-h8 diagonal, so h1 maps to a8
+    pub fn flip_diagonal(&self) -> Bitboard {
+        const K1: u64 = 0x5500_5500_5500_5500;
+        const K2: u64 = 0x3333_0000_3333_0000;
+        const K4: u64 = 0x0F0F_0F0F_0000_0000;
+        let mut bits = self.0;
+        let mut t = K4 & (bits ^ (bits << 28));
+        bits ^= t ^ (t >> 28);
+        t = K2 & (bits ^ (bits << 14));
+        bits ^= t ^ (t >> 14);
+        t = K1 & (bits ^ (bits << 7));
+        bits ^= t ^ (t >> 7);
+        Bitboard(bits)
+    }
+
+    /// Returns the bitboard turned half a circle, so a1 maps to h8
+    pub fn rotate_180(&self) -> Bitboard {
+        Bitboard(self.0.reverse_bits())
+    }
+
+    /// Returns the bitboard with every set bit extended north to the eighth rank
+    pub fn north_fill(&self) -> Bitboard {
+        Bitboard(occluded_fill(self.0, !0, 8))
+    }
+
+    /// Returns the bitboard with every set bit extended south to the first rank
+    pub fn south_fill(&self) -> Bitboard {
+        Bitboard(occluded_fill(self.0, !0, -8))
+    }
+
+    /// Returns the full files of all set bits
+    pub fn file_fill(&self) -> Bitboard {
+        self.north_fill() | self.south_fill()
+    }
+
+    /// Returns the squares in front of the set bits on their files from the given
+    /// color's point of view, excluding the bits themselves
+    pub fn front_span(&self, color: Color) -> Bitboard {
+        match color {
+            Color::White => Bitboard(self.0 << 8).north_fill(),
+            Color::Black => Bitboard(self.0 >> 8).south_fill(),
+        }
+    }
+
+    /// Returns the squares behind the set bits on their files from the given color's
+    /// point of view, excluding the bits themselves
+    pub fn rear_span(&self, color: Color) -> Bitboard {
+        self.front_span(color.opposite())
+    }
+
+    /// Returns the bitboard with every set bit slid north through the `empty` squares,
+    /// stopping before the first occupied square
+    pub fn north_occluded_fill(&self, empty: Bitboard) -> Bitboard {
+        Bitboard(occluded_fill(self.0, empty.0, 8))
+    }
+
+    /// Returns the bitboard with every set bit slid south through the `empty` squares
+    pub fn south_occluded_fill(&self, empty: Bitboard) -> Bitboard {
+        Bitboard(occluded_fill(self.0, empty.0, -8))
+    }
+
+    /// Returns the bitboard with every set bit slid east through the `empty` squares
+    pub fn east_occluded_fill(&self, empty: Bitboard) -> Bitboard {
+        Bitboard(occluded_fill(self.0, empty.0 & !FILE_A, 1))
+    }
+
+    /// Returns the bitboard with every set bit slid west through the `empty` squares
+    pub fn west_occluded_fill(&self, empty: Bitboard) -> Bitboard {
+        Bitboard(occluded_fill(self.0, empty.0 & !FILE_H, -1))
+    }
+
+    /// Returns the bitboard with every set bit slid north-east through the `empty` squares
+    pub fn north_east_occluded_fill(&self, empty: Bitboard) -> Bitboard {
+        Bitboard(occluded_fill(self.0, empty.0 & !FILE_A, 9))
+    }
+
+    /// Returns the bitboard with every set bit slid north-west through the `empty` squares
+    pub fn north_west_occluded_fill(&self, empty: Bitboard) -> Bitboard {
+        Bitboard(occluded_fill(self.0, empty.0 & !FILE_H, 7))
+    }
+
+    /// Returns the bitboard with every set bit slid south-east through the `empty` squares
+    pub fn south_east_occluded_fill(&self, empty: Bitboard) -> Bitboard {
+        Bitboard(occluded_fill(self.0, empty.0 & !FILE_A, -7))
+    }
+
+    /// Returns the bitboard with every set bit slid south-west through the `empty` squares
+    pub fn south_west_occluded_fill(&self, empty: Bitboard) -> Bitboard {
+        Bitboard(occluded_fill(self.0, empty.0 & !FILE_H, -9))
+    }
+}
+
+// Kogge-Stone fill of the generator bits along one direction through the propagator
+// squares, which must already exclude the files a step in that direction wraps onto
+fn occluded_fill(mut generator: u64, mut propagator: u64, step: i8) -> u64 {
+    for distance in [1, 2, 4] {
+        generator |= propagator & shift(generator, step * distance);
+        propagator &= shift(propagator, step * distance);
+    }
+    generator
+}
+
+fn shift(bits: u64, amount: i8) -> u64 {
+    if amount >= 0 {
+        bits << amount
+    } else {
+        bits >> -amount
+    }
 }
 
 impl std::ops::BitAnd for Bitboard {
@@ -129,3 +266,19 @@ impl std::ops::Not for Bitboard {
         Bitboard(!self.0)
     }
 }
+
+/// Iterator over the set squares of a bitboard
+pub struct Squares(u64);
+
+impl Iterator for Squares {
+    type Item = u8;
+    fn next(&mut self) -> Option<u8> {
+        if self.0 == 0 {
+            None
+        } else {
+            let square = self.0.trailing_zeros() as u8;
+            self.0 &= self.0 - 1;
+            Some(square)
+        }
+    }
+}
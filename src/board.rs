@@ -1,14 +1,20 @@
+use crate::attacks::*;
 use crate::bitboard::Bitboard;
 use crate::constants::*;
+use crate::eval::Evaluation;
+use crate::moves::{parse_square, square_name, Move, MoveKind, MoveList};
+use crate::variant::{Outcome, Standard, Variant};
+use crate::zobrist::piece_key;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Color {
     White,
     Black,
 }
 
 impl Color {
-    fn opposite(&self) -> Self {
+    /// Gets the other color
+    pub fn opposite(&self) -> Self {
         match self {
             Color::White => Color::Black,
             Color::Black => Color::White,
@@ -25,7 +31,7 @@ impl std::fmt::Display for Color {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PieceType {
     Pawn,
     Knight,
@@ -35,6 +41,16 @@ pub enum PieceType {
     King,
 }
 
+/// All piece types, from least to most valuable
+pub const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
 impl std::fmt::Display for PieceType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -48,6 +64,112 @@ impl std::fmt::Display for PieceType {
     }
 }
 
+// Castling availability flags
+const WHITE_KINGSIDE: u8 = 1;
+const WHITE_QUEENSIDE: u8 = 2;
+const BLACK_KINGSIDE: u8 = 4;
+const BLACK_QUEENSIDE: u8 = 8;
+const ALL_CASTLING: u8 = WHITE_KINGSIDE | WHITE_QUEENSIDE | BLACK_KINGSIDE | BLACK_QUEENSIDE;
+const STANDARD_CASTLING_ROOKS: [u8; 4] = [7, 0, 63, 56]; // h1, a1, h8, a8
+
+// Knight placements on the five squares left after the bishops and queen, by Scharnagl digit
+const SCHARNAGL_KNIGHTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// Errors that can occur when parsing a FEN string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    WrongFieldCount(usize),
+    InvalidPlacement(String),
+    InvalidSideToMove(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidClock(String),
+    InvalidCheckCount(String),
+}
+
+impl std::fmt::Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FenError::WrongFieldCount(count) => {
+                write!(f, "expected 4 to 6 FEN fields, found {}", count)
+            }
+            FenError::InvalidPlacement(field) => write!(f, "invalid piece placement '{}'", field),
+            FenError::InvalidSideToMove(field) => write!(f, "invalid side to move '{}'", field),
+            FenError::InvalidCastling(field) => write!(f, "invalid castling rights '{}'", field),
+            FenError::InvalidEnPassant(field) => write!(f, "invalid en passant square '{}'", field),
+            FenError::InvalidClock(field) => write!(f, "invalid move clock '{}'", field),
+            FenError::InvalidCheckCount(field) => write!(f, "invalid check counts '{}'", field),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+/// Problems that make a position illegal to play from, reported by `Board::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PositionError {
+    MissingKing(Color),
+    MultipleKings(Color),
+    PawnOnBackRank(u8),
+    OpponentInCheck,
+    InvalidEnPassant(u8),
+    InvalidCastlingRights { color: Color, kingside: bool },
+    OverlappingPieces(u8),
+}
+
+impl std::fmt::Display for PositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionError::MissingKing(color) => write!(f, "{} has no king", color),
+            PositionError::MultipleKings(color) => write!(f, "{} has more than one king", color),
+            PositionError::PawnOnBackRank(square) => {
+                write!(f, "pawn on back rank square {}", square_name(*square))
+            }
+            PositionError::OpponentInCheck => write!(f, "the side not to move is in check"),
+            PositionError::InvalidEnPassant(square) => {
+                write!(f, "invalid en passant square {}", square_name(*square))
+            }
+            PositionError::InvalidCastlingRights { color, kingside } => write!(
+                f,
+                "{} cannot castle {} with the king and rook where they are",
+                color,
+                if *kingside { "kingside" } else { "queenside" }
+            ),
+            PositionError::OverlappingPieces(square) => {
+                write!(f, "more than one piece on {}", square_name(*square))
+            }
+        }
+    }
+}
+
+impl std::error::Error for PositionError {}
+
+// State that cannot be recovered from a move alone, saved by make_move
+#[derive(Debug, Clone, Copy)]
+struct Undo {
+    captured: Option<PieceType>,
+    captured_promoted: bool,
+    promoted: Bitboard,
+    exploded: usize, // Length of the explosion stack before the move
+    castling_rights: u8,
+    en_passant_square: Option<u8>,
+    halfmove_clock: u32,
+    hash: u64, // Hash of the position before the move, for repetition detection
+}
+
 #[derive(Debug, Clone)]
 pub struct Board {
     white_pawns: Bitboard,
@@ -62,7 +184,23 @@ pub struct Board {
     black_rooks: Bitboard,
     black_queens: Bitboard,
     black_king: Bitboard,
+    occupancy: [Bitboard; 2], // All pieces of each color, indexed by Color
+    mailbox: [Option<(PieceType, Color)>; 64], // Piece on each square
     en_passant_square: Option<u8>, // Square behind a pawn that just moved two squares
+    side_to_move: Color,
+    castling_rights: u8,
+    castling_rooks: [u8; 4], // Rook origin for each castling right, indexed by flag bit
+    chess960: bool,          // Castling is written as the king taking its own rook
+    pockets: [[u8; 6]; 2],   // Pieces in hand for drops, indexed by Color and PieceType
+    promoted: Bitboard,      // Pieces that were pawns before promoting
+    exploded: Vec<(PieceType, Color, u8)>, // Pieces removed by atomic explosions
+    checks: [u8; 2],         // Checks given by each color, for three-check
+    evaluation: Evaluation,  // Material and piece-square terms, updated with the pieces
+    piece_hash: u64,         // Zobrist keys of all pieces, updated with the pieces
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    history: Vec<Undo>,
+    variant: &'static dyn Variant,
 }
 
 impl Board {
@@ -81,13 +219,29 @@ impl Board {
             black_rooks: Bitboard::empty(),
             black_queens: Bitboard::empty(),
             black_king: Bitboard::empty(),
+            occupancy: [Bitboard::empty(); 2],
+            mailbox: [None; 64],
             en_passant_square: None,
+            side_to_move: Color::White,
+            castling_rights: 0,
+            castling_rooks: STANDARD_CASTLING_ROOKS,
+            chess960: false,
+            pockets: [[0; 6]; 2],
+            promoted: Bitboard::empty(),
+            exploded: Vec::new(),
+            checks: [0; 2],
+            evaluation: Evaluation::default(),
+            piece_hash: 0,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            history: Vec::new(),
+            variant: &Standard,
         }
     }
 
     /// Creates a new board with pieces in their initial positions
     pub fn initial() -> Self {
-        Board {
+        let mut board = Board {
             white_pawns: Bitboard::from_u64(WHITE_PAWNS_INIT),
             white_knights: Bitboard::from_u64(WHITE_KNIGHTS_INIT),
             white_bishops: Bitboard::from_u64(WHITE_BISHOPS_INIT),
@@ -100,30 +254,105 @@ impl Board {
             black_rooks: Bitboard::from_u64(BLACK_ROOKS_INIT),
             black_queens: Bitboard::from_u64(BLACK_QUEENS_INIT),
             black_king: Bitboard::from_u64(BLACK_KING_INIT),
+            occupancy: [Bitboard::empty(); 2],
+            mailbox: [None; 64],
             en_passant_square: None,
+            side_to_move: Color::White,
+            castling_rights: ALL_CASTLING,
+            castling_rooks: STANDARD_CASTLING_ROOKS,
+            chess960: false,
+            pockets: [[0; 6]; 2],
+            promoted: Bitboard::empty(),
+            exploded: Vec::new(),
+            checks: [0; 2],
+            evaluation: Evaluation::default(),
+            piece_hash: 0,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            history: Vec::new(),
+            variant: &Standard,
+        };
+        board.refresh_occupancy();
+        board
+    }
+
+    /// Creates the Chess960 start position with the given Scharnagl number (0-959).
+    /// Number 518 is the standard start position.
+    pub fn chess960(number: u32) -> Option<Self> {
+        if number >= 960 {
+            return None;
         }
+
+        let mut back_rank: [Option<PieceType>; 8] = [None; 8];
+        let mut n = number as usize;
+        back_rank[(n % 4) * 2 + 1] = Some(PieceType::Bishop);
+        n /= 4;
+        back_rank[(n % 4) * 2] = Some(PieceType::Bishop);
+        n /= 4;
+        let empty = |rank: &[Option<PieceType>; 8]| -> Vec<usize> {
+            (0..8).filter(|&file| rank[file].is_none()).collect()
+        };
+        back_rank[empty(&back_rank)[n % 6]] = Some(PieceType::Queen);
+        n /= 6;
+        let free = empty(&back_rank);
+        let (first, second) = SCHARNAGL_KNIGHTS[n];
+        back_rank[free[first]] = Some(PieceType::Knight);
+        back_rank[free[second]] = Some(PieceType::Knight);
+        let free = empty(&back_rank);
+        back_rank[free[0]] = Some(PieceType::Rook);
+        back_rank[free[1]] = Some(PieceType::King);
+        back_rank[free[2]] = Some(PieceType::Rook);
+
+        let mut board = Board::empty();
+        for (file, piece_type) in back_rank.iter().enumerate() {
+            let piece_type = piece_type.expect("every back rank square is filled");
+            board.add_piece(piece_type, Color::White, file as u8);
+            board.add_piece(piece_type, Color::Black, 56 + file as u8);
+            board.add_piece(PieceType::Pawn, Color::White, 8 + file as u8);
+            board.add_piece(PieceType::Pawn, Color::Black, 48 + file as u8);
+        }
+        board.castling_rights = ALL_CASTLING;
+        board.castling_rooks = [
+            free[2] as u8,
+            free[0] as u8,
+            56 + free[2] as u8,
+            56 + free[0] as u8,
+        ];
+        board.chess960 = true;
+        Some(board)
     }
 
-    /// Gets all pieces of a given color
-    pub fn get_color_pieces(&self, color: Color) -> Bitboard {
-        match color {
-            Color::White => {
-                self.white_pawns
-                    | self.white_knights
-                    | self.white_bishops
-                    | self.white_rooks
-                    | self.white_queens
-                    | self.white_king
-            }
-            Color::Black => {
-                self.black_pawns
-                    | self.black_knights
-                    | self.black_bishops
-                    | self.black_rooks
-                    | self.black_queens
-                    | self.black_king
+    /// Gets the same position with the colors swapped and the board mirrored top to
+    /// bottom, so White's pieces become Black's on the opposite ranks. The side to move,
+    /// castling rights, en passant square, pockets and check counts follow, while the
+    /// move history is not carried over.
+    pub fn color_flipped(&self) -> Board {
+        let mut board = Board::empty();
+        for color in [Color::White, Color::Black] {
+            for piece_type in PIECE_TYPES {
+                for square in self.get_pieces(piece_type, color).squares() {
+                    board.add_piece(piece_type, color.opposite(), square ^ 56);
+                }
             }
         }
+        board.side_to_move = self.side_to_move.opposite();
+        board.castling_rights = ((self.castling_rights & 3) << 2) | (self.castling_rights >> 2);
+        let rooks = self.castling_rooks;
+        board.castling_rooks = [rooks[2] ^ 56, rooks[3] ^ 56, rooks[0] ^ 56, rooks[1] ^ 56];
+        board.chess960 = self.chess960;
+        board.en_passant_square = self.en_passant_square.map(|square| square ^ 56);
+        board.pockets = [self.pockets[1], self.pockets[0]];
+        board.promoted = self.promoted.flip_vertical();
+        board.checks = [self.checks[1], self.checks[0]];
+        board.halfmove_clock = self.halfmove_clock;
+        board.fullmove_number = self.fullmove_number;
+        board.variant = self.variant;
+        board
+    }
+
+    /// Gets all pieces of a given color
+    pub fn get_color_pieces(&self, color: Color) -> Bitboard {
+        self.occupancy[color as usize]
     }
 
     /// Gets all pieces of a given type and color
@@ -151,34 +380,885 @@ impl Board {
 
     /// Gets the piece type at a given square, if any
     pub fn get_piece_at(&self, square: u8) -> Option<(PieceType, Color)> {
-        let white_pieces = self.get_color_pieces(Color::White);
-        let black_pieces = self.get_color_pieces(Color::Black);
+        self.mailbox[square as usize]
+    }
 
-        if !white_pieces.test_bit(square) && !black_pieces.test_bit(square) {
-            return None;
+    /// Gets the color whose turn it is to move
+    pub fn side_to_move(&self) -> Color {
+        self.side_to_move
+    }
+
+    /// Gets the en passant target square, if the last move was a double pawn push
+    pub fn en_passant_square(&self) -> Option<u8> {
+        self.en_passant_square
+    }
+
+    /// Returns true if the given color still has the right to castle kingside
+    pub fn can_castle_kingside(&self, color: Color) -> bool {
+        self.castling_rights & castling_flag(color, true) != 0
+    }
+
+    /// Returns true if the given color still has the right to castle queenside
+    pub fn can_castle_queenside(&self, color: Color) -> bool {
+        self.castling_rights & castling_flag(color, false) != 0
+    }
+
+    /// Gets the square of the rook used for castling on the given side, if the right remains
+    pub fn castling_rook(&self, color: Color, kingside: bool) -> Option<u8> {
+        let flag = castling_flag(color, kingside);
+        if self.castling_rights & flag != 0 {
+            Some(self.castling_rooks[flag.trailing_zeros() as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if castling moves are written as the king taking its own rook, as in
+    /// Chess960. Set for Chess960 start positions and FENs whose castling rights need a
+    /// king or rook off the standard squares.
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
+    }
+
+    /// Sets whether castling moves are written as the king taking its own rook, e.g. for
+    /// a Chess960 game that started from the standard position
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.chess960 = chess960;
+    }
+
+    /// Gets the number of pieces of a type the given color holds in its pocket for drops
+    pub fn pocket(&self, color: Color, piece_type: PieceType) -> u8 {
+        self.pockets[color as usize][piece_type as usize]
+    }
+
+    /// Returns true if the given color holds any pieces in its pocket
+    pub fn has_pocket_pieces(&self, color: Color) -> bool {
+        self.pockets[color as usize].iter().any(|&count| count > 0)
+    }
+
+    /// Adds a piece to the given color's pocket
+    pub fn add_to_pocket(&mut self, color: Color, piece_type: PieceType) {
+        self.pockets[color as usize][piece_type as usize] += 1;
+    }
+
+    /// Removes a piece from the given color's pocket, returning false if there was none
+    pub fn remove_from_pocket(&mut self, color: Color, piece_type: PieceType) -> bool {
+        let count = &mut self.pockets[color as usize][piece_type as usize];
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+
+    /// Gets the pieces that were promoted from pawns, which revert to pawns when captured
+    /// in crazyhouse
+    pub fn promoted(&self) -> Bitboard {
+        self.promoted
+    }
+
+    // Gets the castling rights as flag bits
+    pub(crate) fn castling_rights(&self) -> u8 {
+        self.castling_rights
+    }
+
+    // Gets the Zobrist keys of all pieces xored together
+    pub(crate) fn piece_hash(&self) -> u64 {
+        self.piece_hash
+    }
+
+    /// Gets the evaluation terms, updated incrementally as pieces are added and removed
+    pub fn evaluation(&self) -> &Evaluation {
+        &self.evaluation
+    }
+
+    /// Gets the number of checks the given color has delivered, tracked by three-check
+    pub fn checks_given(&self, color: Color) -> u8 {
+        self.checks[color as usize]
+    }
+
+    /// Counts a check delivered by the given color
+    pub fn add_check(&mut self, color: Color) {
+        self.checks[color as usize] += 1;
+    }
+
+    /// Takes back a check counted with `add_check`
+    pub fn remove_check(&mut self, color: Color) {
+        self.checks[color as usize] = self.checks[color as usize].saturating_sub(1);
+    }
+
+    // Gets the piece captured by the last move and whether it had been promoted
+    pub(crate) fn last_capture(&self) -> Option<(PieceType, bool)> {
+        let undo = self.history.last()?;
+        undo.captured
+            .map(|captured| (captured, undo.captured_promoted))
+    }
+
+    // Removes a piece blown up by the last move, so it comes back with restore_exploded
+    pub(crate) fn explode(&mut self, square: u8) {
+        if let Some((piece_type, color)) = self.get_piece_at(square) {
+            self.remove_piece(piece_type, color, square);
+            self.promoted.clear_bit(square);
+            let mut lost = self.castling_rights_lost(square);
+            if piece_type == PieceType::King {
+                lost |= castling_flag(color, true) | castling_flag(color, false);
+            }
+            self.castling_rights &= !lost;
+            self.exploded.push((piece_type, color, square));
+        }
+    }
+
+    // Puts back the pieces removed with explode during the last move
+    pub(crate) fn restore_exploded(&mut self) {
+        let start = self.history.last().map_or(0, |undo| undo.exploded);
+        while self.exploded.len() > start {
+            let (piece_type, color, square) = self.exploded.pop().unwrap();
+            self.add_piece(piece_type, color, square);
+        }
+    }
+
+    // Origin and destination of the rook when castling
+    pub(crate) fn castling_rook_squares(&self, color: Color, kingside: bool) -> (u8, u8) {
+        let flag = castling_flag(color, kingside);
+        let rook_from = self.castling_rooks[flag.trailing_zeros() as usize];
+        let base = rook_from & 56;
+        if kingside {
+            (rook_from, base + 5)
+        } else {
+            (rook_from, base + 3)
+        }
+    }
+
+    // Castling rights that are lost when a piece moves from or to the square
+    fn castling_rights_lost(&self, square: u8) -> u8 {
+        let mut lost = 0;
+        for (index, &rook) in self.castling_rooks.iter().enumerate() {
+            if rook == square {
+                lost |= 1 << index;
+            }
+        }
+        lost
+    }
+
+    /// Gets the number of half moves since the last capture or pawn move
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    /// Gets the full move number, starting at 1 and incremented after Black moves
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    /// Gets the square of the given color's king, if it has one
+    pub fn king_square(&self, color: Color) -> Option<u8> {
+        self.get_pieces(PieceType::King, color).lsb()
+    }
+
+    /// Gets all pieces of either color attacking a square, with sliders blocked by `occupied`
+    pub fn attackers_to(&self, square: u8, occupied: Bitboard) -> Bitboard {
+        let knights = self.white_knights | self.black_knights;
+        let kings = self.white_king | self.black_king;
+        let diagonal =
+            self.white_bishops | self.black_bishops | self.white_queens | self.black_queens;
+        let orthogonal =
+            self.white_rooks | self.black_rooks | self.white_queens | self.black_queens;
+
+        (pawn_attacks(Color::Black, square) & self.white_pawns)
+            | (pawn_attacks(Color::White, square) & self.black_pawns)
+            | (knight_attacks(square) & knights)
+            | (king_attacks(square) & kings)
+            | (bishop_attacks(square, occupied) & diagonal)
+            | (rook_attacks(square, occupied) & orthogonal)
+    }
+
+    /// Returns true if any piece of the given color attacks the square
+    pub fn is_square_attacked(&self, square: u8, by: Color) -> bool {
+        (self.attackers_to(square, self.get_all_pieces()) & self.get_color_pieces(by)).as_u64() != 0
+    }
+
+    /// Gets the enemy pieces giving check to the side to move
+    pub fn checkers(&self) -> Bitboard {
+        match self.king_square(self.side_to_move) {
+            Some(king) => {
+                self.attackers_to(king, self.get_all_pieces())
+                    & self.get_color_pieces(self.side_to_move.opposite())
+            }
+            None => Bitboard::empty(),
+        }
+    }
+
+    /// Returns true if the side to move is in check
+    pub fn is_in_check(&self) -> bool {
+        self.checkers().as_u64() != 0
+    }
+
+    /// Gets the pieces of the given color that are absolutely pinned to their own king
+    pub fn pinned(&self, color: Color) -> Bitboard {
+        self.slider_blockers(color, color.opposite()).0 & self.get_color_pieces(color)
+    }
+
+    /// Gets the sliders of the given color that pin an enemy piece to the enemy king
+    pub fn pinners(&self, color: Color) -> Bitboard {
+        let (blockers, snipers) = self.slider_blockers(color.opposite(), color);
+        let pinned = blockers & self.get_color_pieces(color.opposite());
+        let mut pinners = Bitboard::empty();
+        if let Some(king) = self.king_square(color.opposite()) {
+            for sniper in snipers.squares() {
+                if !(Bitboard::from_u64(between(king, sniper)) & pinned).is_empty() {
+                    pinners.set_bit(sniper);
+                }
+            }
+        }
+        pinners
+    }
+
+    /// Gets the pieces of the given color that would uncover a slider attack on the
+    /// enemy king by moving off their line
+    pub fn discovered_check_candidates(&self, color: Color) -> Bitboard {
+        self.slider_blockers(color.opposite(), color).0 & self.get_color_pieces(color)
+    }
+
+    /// Creates the start position of a variant
+    pub fn variant_start(variant: &'static dyn Variant) -> Self {
+        Board::from_variant_fen(variant.start_fen(), variant).expect("variant start FEN is valid")
+    }
+
+    /// Creates a board following a variant's rules from a FEN string
+    pub fn from_variant_fen(fen: &str, variant: &'static dyn Variant) -> Result<Board, FenError> {
+        let mut board = Board::from_fen(fen)?;
+        board.set_variant(variant);
+        Ok(board)
+    }
+
+    /// Gets the variant whose rules the board follows
+    pub fn variant(&self) -> &'static dyn Variant {
+        self.variant
+    }
+
+    /// Switches the rules the board follows, keeping the position except for castling
+    /// rights the new variant does not have
+    pub fn set_variant(&mut self, variant: &'static dyn Variant) {
+        self.variant = variant;
+        if !variant.has_castling() {
+            self.castling_rights = 0;
+        }
+    }
+
+    /// Gets the result if the game is over, following the board's variant
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.variant.outcome(self)
+    }
+
+    /// Gets the result under standard rules: checkmate, stalemate, a dead position
+    /// or the fifty-move rule
+    pub fn standard_outcome(&self) -> Option<Outcome> {
+        let mut moves = MoveList::new();
+        self.generate_standard_moves(&mut moves);
+        if moves.is_empty() {
+            if self.is_in_check() {
+                return Some(Outcome::Win(self.side_to_move.opposite()));
+            }
+            return Some(Outcome::Draw);
+        }
+        if self.halfmove_clock >= 100 || self.is_dead_position() {
+            return Some(Outcome::Draw);
+        }
+        None
+    }
+
+    /// Applies a legal move for the side to move following the board's variant;
+    /// it can be reverted with `unmake_move`
+    pub fn make_move(&mut self, mv: Move) {
+        self.variant.make_move(self, mv);
+    }
+
+    /// Reverts the last move applied with `make_move`
+    pub fn unmake_move(&mut self, mv: Move) {
+        self.variant.unmake_move(self, mv);
+    }
+
+    /// Applies a legal move under standard rules
+    pub fn make_standard_move(&mut self, mv: Move) {
+        let us = self.side_to_move;
+        let them = us.opposite();
+        let (from, to) = (mv.from(), mv.to());
+
+        if let Some(piece_type) = mv.drop_piece() {
+            self.history.push(Undo {
+                captured: None,
+                captured_promoted: false,
+                promoted: self.promoted,
+                exploded: self.exploded.len(),
+                castling_rights: self.castling_rights,
+                en_passant_square: self.en_passant_square,
+                halfmove_clock: self.halfmove_clock,
+                hash: self.hash(),
+            });
+            self.pockets[us as usize][piece_type as usize] -= 1;
+            self.add_piece(piece_type, us, to);
+            self.en_passant_square = None;
+            self.halfmove_clock += 1;
+            if us == Color::Black {
+                self.fullmove_number += 1;
+            }
+            self.side_to_move = them;
+            return;
+        }
+
+        let (piece_type, _) = self
+            .get_piece_at(from)
+            .expect("make_move called with no piece on the origin square");
+
+        let captured = match mv.kind() {
+            MoveKind::EnPassant => Some(PieceType::Pawn),
+            MoveKind::Capture | MoveKind::PromotionCapture(_) => {
+                self.get_piece_at(to).map(|(captured, _)| captured)
+            }
+            _ => None,
+        };
+
+        self.history.push(Undo {
+            captured,
+            captured_promoted: captured.is_some() && self.promoted.test_bit(capture_square(mv, us)),
+            promoted: self.promoted,
+            exploded: self.exploded.len(),
+            castling_rights: self.castling_rights,
+            en_passant_square: self.en_passant_square,
+            halfmove_clock: self.halfmove_clock,
+            hash: self.hash(),
+        });
+
+        self.promoted.clear_bit(capture_square(mv, us));
+        if self.promoted.test_bit(from) || mv.is_promotion() {
+            self.promoted.clear_bit(from);
+            self.promoted.set_bit(to);
+        }
+
+        if let Some(captured) = captured {
+            self.remove_piece(captured, them, capture_square(mv, us));
+        }
+        if mv.is_castle() {
+            // Both pieces come off first, since in Chess960 the king may land on the
+            // rook's square or the other way round
+            let (rook_from, rook_to) =
+                self.castling_rook_squares(us, mv.kind() == MoveKind::KingCastle);
+            self.remove_piece(PieceType::King, us, from);
+            self.remove_piece(PieceType::Rook, us, rook_from);
+            self.add_piece(PieceType::King, us, to);
+            self.add_piece(PieceType::Rook, us, rook_to);
+        } else {
+            self.remove_piece(piece_type, us, from);
+            self.add_piece(mv.promotion().unwrap_or(piece_type), us, to);
+        }
+
+        let mut lost = self.castling_rights_lost(from) | self.castling_rights_lost(to);
+        if piece_type == PieceType::King {
+            lost |= castling_flag(us, true) | castling_flag(us, false);
+        }
+        self.castling_rights &= !lost;
+        self.en_passant_square = match mv.kind() {
+            MoveKind::DoublePawnPush => Some((from + to) / 2),
+            _ => None,
+        };
+        if piece_type == PieceType::Pawn || captured.is_some() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if us == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.side_to_move = them;
+    }
+
+    /// Reverts the last move applied with `make_standard_move`
+    pub fn unmake_standard_move(&mut self, mv: Move) {
+        let undo = self
+            .history
+            .pop()
+            .expect("unmake_move called without a matching make_move");
+        let them = self.side_to_move;
+        let us = them.opposite();
+        let (from, to) = (mv.from(), mv.to());
+
+        self.side_to_move = us;
+        if us == Color::Black {
+            self.fullmove_number -= 1;
+        }
+        self.castling_rights = undo.castling_rights;
+        self.en_passant_square = undo.en_passant_square;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.promoted = undo.promoted;
+
+        if let Some(piece_type) = mv.drop_piece() {
+            self.remove_piece(piece_type, us, to);
+            self.pockets[us as usize][piece_type as usize] += 1;
+            return;
         }
 
-        let color = if white_pieces.test_bit(square) {
+        if mv.is_castle() {
+            let (rook_from, rook_to) =
+                self.castling_rook_squares(us, mv.kind() == MoveKind::KingCastle);
+            self.remove_piece(PieceType::King, us, to);
+            self.remove_piece(PieceType::Rook, us, rook_to);
+            self.add_piece(PieceType::King, us, from);
+            self.add_piece(PieceType::Rook, us, rook_from);
+            return;
+        }
+
+        let (piece_type, _) = self
+            .get_piece_at(to)
+            .expect("unmake_move called with no piece on the destination square");
+        self.remove_piece(piece_type, us, to);
+        let original = if mv.is_promotion() {
+            PieceType::Pawn
+        } else {
+            piece_type
+        };
+        self.add_piece(original, us, from);
+
+        if let Some(captured) = undo.captured {
+            self.add_piece(captured, them, capture_square(mv, us));
+        }
+    }
+
+    /// Creates a board from a FEN string. The move clocks may be omitted.
+    pub fn from_fen(fen: &str) -> Result<Board, FenError> {
+        let mut fields: Vec<&str> = fen.split_whitespace().collect();
+        let mut board = Board::empty();
+
+        // Three-check counts follow the other fields as "+white+black"
+        if let Some(field) = fields.last().filter(|field| field.starts_with('+')) {
+            let counts: Vec<&str> = field[1..].split('+').collect();
+            match counts.as_slice() {
+                [white, black] => {
+                    for (color, count) in [(Color::White, white), (Color::Black, black)] {
+                        board.checks[color as usize] = count
+                            .parse()
+                            .map_err(|_| FenError::InvalidCheckCount(field.to_string()))?;
+                    }
+                }
+                _ => return Err(FenError::InvalidCheckCount(field.to_string())),
+            }
+            fields.pop();
+        }
+        if fields.len() < 4 || fields.len() > 6 {
+            return Err(FenError::WrongFieldCount(fields.len()));
+        }
+
+        let placement_error = || FenError::InvalidPlacement(fields[0].to_string());
+
+        // Crazyhouse pockets follow the placement in brackets or as a ninth rank
+        let (placement, pocket) = match fields[0].strip_suffix(']') {
+            Some(rest) => rest.split_once('[').ok_or_else(placement_error)?,
+            None => (fields[0], ""),
+        };
+        let mut ranks: Vec<&str> = placement.split('/').collect();
+        let pocket = match ranks.len() {
+            9 if pocket.is_empty() => ranks.pop().unwrap(),
+            8 => pocket,
+            _ => return Err(placement_error()),
+        };
+        for c in pocket.chars() {
+            match piece_from_char(c) {
+                Some((piece_type, color)) if piece_type != PieceType::King => {
+                    board.add_to_pocket(color, piece_type)
+                }
+                _ => return Err(placement_error()),
+            }
+        }
+
+        for (i, rank_str) in ranks.iter().enumerate() {
+            let rank = 7 - i as u8;
+            let mut file = 0u8;
+            for c in rank_str.chars() {
+                if c == '~' {
+                    // Marks the previous piece as promoted
+                    if file == 0 {
+                        return Err(placement_error());
+                    }
+                    board.promoted.set_bit(rank * 8 + file - 1);
+                    continue;
+                }
+                if let Some(skip) = c.to_digit(10) {
+                    if !(1..=8).contains(&skip) {
+                        return Err(placement_error());
+                    }
+                    file += skip as u8;
+                } else {
+                    let (piece_type, color) = piece_from_char(c).ok_or_else(placement_error)?;
+                    if file >= 8 {
+                        return Err(placement_error());
+                    }
+                    board.add_piece(piece_type, color, rank * 8 + file);
+                    file += 1;
+                }
+                if file > 8 {
+                    return Err(placement_error());
+                }
+            }
+            if file != 8 {
+                return Err(placement_error());
+            }
+        }
+
+        board.side_to_move = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            other => return Err(FenError::InvalidSideToMove(other.to_string())),
+        };
+
+        if fields[2] != "-" {
+            for c in fields[2].chars() {
+                board
+                    .parse_castling_right(c)
+                    .ok_or_else(|| FenError::InvalidCastling(fields[2].to_string()))?;
+            }
+        }
+        board.chess960 = board.has_chess960_castling();
+
+        if fields[3] != "-" {
+            match parse_square(fields[3]) {
+                Some(square) if square / 8 == 2 || square / 8 == 5 => {
+                    board.en_passant_square = Some(square)
+                }
+                _ => return Err(FenError::InvalidEnPassant(fields[3].to_string())),
+            }
+        }
+
+        if let Some(field) = fields.get(4) {
+            board.halfmove_clock = field
+                .parse()
+                .map_err(|_| FenError::InvalidClock(field.to_string()))?;
+        }
+        if let Some(field) = fields.get(5) {
+            board.fullmove_number = field
+                .parse()
+                .map_err(|_| FenError::InvalidClock(field.to_string()))?;
+        }
+
+        Ok(board)
+    }
+
+    /// Formats the position as a FEN string
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+        let pockets = self.variant.has_pockets();
+
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.get_piece_at(rank * 8 + file) {
+                    Some((piece_type, color)) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        fen.push(piece_char(piece_type, color));
+                        if pockets && self.promoted.test_bit(rank * 8 + file) {
+                            fen.push('~');
+                        }
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+
+        if pockets {
+            fen.push('[');
+            for color in [Color::White, Color::Black] {
+                for &piece_type in PIECE_TYPES[..5].iter().rev() {
+                    for _ in 0..self.pocket(color, piece_type) {
+                        fen.push(piece_char(piece_type, color));
+                    }
+                }
+            }
+            fen.push(']');
+        }
+
+        fen.push_str(match self.side_to_move {
+            Color::White => " w ",
+            Color::Black => " b ",
+        });
+
+        if self.castling_rights == 0 {
+            fen.push('-');
+        }
+        for color in [Color::White, Color::Black] {
+            for kingside in [true, false] {
+                if let Some(c) = self.castling_char(color, kingside) {
+                    fen.push(c);
+                }
+            }
+        }
+
+        match self.en_passant_square {
+            Some(square) => fen.push_str(&format!(" {}", square_name(square))),
+            None => fen.push_str(" -"),
+        }
+
+        fen.push_str(&format!(
+            " {} {}",
+            self.halfmove_clock, self.fullmove_number
+        ));
+        if self.variant.counts_checks() {
+            fen.push_str(&format!(" +{}+{}", self.checks[0], self.checks[1]));
+        }
+        fen
+    }
+
+    // Adds the castling right for an X-FEN or Shredder-FEN character. K/Q select the
+    // outermost rook on that side of the king, a file letter selects the rook directly.
+    fn parse_castling_right(&mut self, c: char) -> Option<()> {
+        let color = if c.is_ascii_uppercase() {
             Color::White
         } else {
             Color::Black
         };
+        let base = match color {
+            Color::White => 0,
+            Color::Black => 56,
+        };
+        let king_file = match self.king_square(color) {
+            Some(king) if king & 56 == base => king % 8,
+            _ => 4,
+        };
+        let rooks = self.get_pieces(PieceType::Rook, color).as_u64() >> base & 0xFF;
 
-        let piece_type = if self.get_pieces(PieceType::Pawn, color).test_bit(square) {
-            PieceType::Pawn
-        } else if self.get_pieces(PieceType::Knight, color).test_bit(square) {
-            PieceType::Knight
-        } else if self.get_pieces(PieceType::Bishop, color).test_bit(square) {
-            PieceType::Bishop
-        } else if self.get_pieces(PieceType::Rook, color).test_bit(square) {
-            PieceType::Rook
-        } else if self.get_pieces(PieceType::Queen, color).test_bit(square) {
-            PieceType::Queen
+        let (kingside, file) = match c.to_ascii_lowercase() {
+            'k' => {
+                let outer = rooks & !((2 << king_file) - 1);
+                match outer {
+                    0 => (true, 7),
+                    _ => (true, 63 - outer.leading_zeros() as u8),
+                }
+            }
+            'q' => {
+                let outer = rooks & ((1 << king_file) - 1);
+                match outer {
+                    0 => (false, 0),
+                    _ => (false, outer.trailing_zeros() as u8),
+                }
+            }
+            file @ 'a'..='h' => {
+                let file = file as u8 - b'a';
+                if file == king_file {
+                    return None;
+                }
+                (file > king_file, file)
+            }
+            _ => return None,
+        };
+
+        let flag = castling_flag(color, kingside);
+        self.castling_rights |= flag;
+        self.castling_rooks[flag.trailing_zeros() as usize] = base + file;
+        Some(())
+    }
+
+    // Returns true if a castling right has its king or rook off the standard squares
+    fn has_chess960_castling(&self) -> bool {
+        [Color::White, Color::Black].into_iter().any(|color| {
+            [true, false].into_iter().any(|kingside| {
+                self.castling_rook(color, kingside).is_some_and(|rook| {
+                    let base = rook & 56;
+                    let corner = if kingside { base + 7 } else { base };
+                    rook != corner || self.king_square(color) != Some(base + 4)
+                })
+            })
+        })
+    }
+
+    // Gets the X-FEN character for a castling right: K/Q unless another rook stands
+    // further out on the same side, in which case the rook's file is given
+    fn castling_char(&self, color: Color, kingside: bool) -> Option<char> {
+        let rook = self.castling_rook(color, kingside)?;
+        let base = rook & 56;
+        let rooks = self.get_pieces(PieceType::Rook, color);
+        let outer = if kingside {
+            rook + 1..base + 8
+        } else {
+            base..rook
+        };
+        let c = if outer.into_iter().any(|square| rooks.test_bit(square)) {
+            (b'a' + rook % 8) as char
+        } else if kingside {
+            'k'
         } else {
-            PieceType::King
+            'q'
         };
+        Some(match color {
+            Color::White => c.to_ascii_uppercase(),
+            Color::Black => c,
+        })
+    }
+
+    /// Checks that the position is legal to play from, reporting every problem found
+    pub fn validate(&self) -> Result<(), Vec<PositionError>> {
+        let mut errors = Vec::new();
+
+        let mut seen = Bitboard::empty();
+        let mut overlapping = Bitboard::empty();
+        for color in [Color::White, Color::Black] {
+            for piece_type in PIECE_TYPES {
+                let pieces = self.get_pieces(piece_type, color);
+                overlapping = overlapping | (seen & pieces);
+                seen = seen | pieces;
+            }
+        }
+        for square in overlapping.squares() {
+            errors.push(PositionError::OverlappingPieces(square));
+        }
+
+        let mut kings_valid = true;
+        for color in [Color::White, Color::Black] {
+            match self.get_pieces(PieceType::King, color).pop_count() {
+                0 => errors.push(PositionError::MissingKing(color)),
+                1 => continue,
+                _ => errors.push(PositionError::MultipleKings(color)),
+            }
+            kings_valid = false;
+        }
 
-        Some((piece_type, color))
+        let pawns = self.white_pawns | self.black_pawns;
+        for square in (pawns & Bitboard::from_u64(RANK_1 | RANK_8)).squares() {
+            errors.push(PositionError::PawnOnBackRank(square));
+        }
+
+        if kings_valid {
+            let them = self.side_to_move.opposite();
+            let king = self.king_square(them).unwrap();
+            if self.is_square_attacked(king, self.side_to_move) {
+                errors.push(PositionError::OpponentInCheck);
+            }
+        }
+
+        if let Some(square) = self.en_passant_square {
+            if !self.is_valid_en_passant(square) {
+                errors.push(PositionError::InvalidEnPassant(square));
+            }
+        }
+
+        for color in [Color::White, Color::Black] {
+            for kingside in [true, false] {
+                if self.castling_rights & castling_flag(color, kingside) == 0 {
+                    continue;
+                }
+                let (rook_home, _) = self.castling_rook_squares(color, kingside);
+                let king_in_place = self.king_square(color).is_some_and(|king| {
+                    king & 56 == rook_home & 56 && (rook_home > king) == kingside
+                });
+                if !king_in_place || !self.get_pieces(PieceType::Rook, color).test_bit(rook_home) {
+                    errors.push(PositionError::InvalidCastlingRights { color, kingside });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // The en passant square must lie behind an enemy pawn that could just have double-pushed
+    fn is_valid_en_passant(&self, square: u8) -> bool {
+        let them = self.side_to_move.opposite();
+        let (rank, pawn_square, origin) = match self.side_to_move {
+            Color::White => (5, square.wrapping_sub(8), square.wrapping_add(8)),
+            Color::Black => (2, square.wrapping_add(8), square.wrapping_sub(8)),
+        };
+        square / 8 == rank
+            && self.get_pieces(PieceType::Pawn, them).test_bit(pawn_square)
+            && self.get_piece_at(square).is_none()
+            && self.get_piece_at(origin).is_none()
+    }
+
+    // Gets the single pieces (of either color) standing between the king of `king_color`
+    // and a `slider_color` slider aiming at it, along with those sliders
+    fn slider_blockers(&self, king_color: Color, slider_color: Color) -> (Bitboard, Bitboard) {
+        let mut blockers = Bitboard::empty();
+        let mut snipers = Bitboard::empty();
+        let king = match self.king_square(king_color) {
+            Some(king) => king,
+            None => return (blockers, snipers),
+        };
+
+        let queens = self.get_pieces(PieceType::Queen, slider_color);
+        let candidates = (rook_attacks(king, Bitboard::empty())
+            & (self.get_pieces(PieceType::Rook, slider_color) | queens))
+            | (bishop_attacks(king, Bitboard::empty())
+                & (self.get_pieces(PieceType::Bishop, slider_color) | queens));
+
+        let occupied = self.get_all_pieces();
+        for sniper in candidates.squares() {
+            let blocking = Bitboard::from_u64(between(king, sniper)) & occupied;
+            if blocking.pop_count() == 1 {
+                blockers = blockers | blocking;
+                snipers.set_bit(sniper);
+            }
+        }
+        (blockers, snipers)
+    }
+
+    fn pieces_mut(&mut self, piece_type: PieceType, color: Color) -> &mut Bitboard {
+        match (color, piece_type) {
+            (Color::White, PieceType::Pawn) => &mut self.white_pawns,
+            (Color::White, PieceType::Knight) => &mut self.white_knights,
+            (Color::White, PieceType::Bishop) => &mut self.white_bishops,
+            (Color::White, PieceType::Rook) => &mut self.white_rooks,
+            (Color::White, PieceType::Queen) => &mut self.white_queens,
+            (Color::White, PieceType::King) => &mut self.white_king,
+            (Color::Black, PieceType::Pawn) => &mut self.black_pawns,
+            (Color::Black, PieceType::Knight) => &mut self.black_knights,
+            (Color::Black, PieceType::Bishop) => &mut self.black_bishops,
+            (Color::Black, PieceType::Rook) => &mut self.black_rooks,
+            (Color::Black, PieceType::Queen) => &mut self.black_queens,
+            (Color::Black, PieceType::King) => &mut self.black_king,
+        }
+    }
+
+    fn add_piece(&mut self, piece_type: PieceType, color: Color, square: u8) {
+        self.pieces_mut(piece_type, color).set_bit(square);
+        self.occupancy[color as usize].set_bit(square);
+        self.mailbox[square as usize] = Some((piece_type, color));
+        self.evaluation.add_piece(piece_type, color, square);
+        self.piece_hash ^= piece_key(piece_type, color, square);
+    }
+
+    fn remove_piece(&mut self, piece_type: PieceType, color: Color, square: u8) {
+        self.pieces_mut(piece_type, color).clear_bit(square);
+        self.occupancy[color as usize].clear_bit(square);
+        self.mailbox[square as usize] = None;
+        self.evaluation.remove_piece(piece_type, color, square);
+        self.piece_hash ^= piece_key(piece_type, color, square);
+    }
+
+    // Rebuilds the occupancy bitboards, mailbox, evaluation terms and piece hash from the
+    // piece bitboards
+    fn refresh_occupancy(&mut self) {
+        self.occupancy = [Bitboard::empty(); 2];
+        self.mailbox = [None; 64];
+        self.piece_hash = 0;
+        // If a square was set twice, White and then the lower piece type win
+        for color in [Color::Black, Color::White] {
+            for &piece_type in PIECE_TYPES.iter().rev() {
+                let pieces = self.get_pieces(piece_type, color);
+                self.occupancy[color as usize] = self.occupancy[color as usize] | pieces;
+                for square in pieces.squares() {
+                    self.mailbox[square as usize] = Some((piece_type, color));
+                    self.piece_hash ^= piece_key(piece_type, color, square);
+                }
+            }
+        }
+        self.evaluation = Evaluation::compute(self);
     }
 
     /// Places a piece on the board, handling captures and en passant
@@ -235,10 +1315,10 @@ impl Board {
         // Update en passant square if this is a pawn moving two squares
         if piece_type == PieceType::Pawn {
             match color {
-                Color::White if square >= 24 && square < 32 => {
+                Color::White if (24..32).contains(&square) => {
                     self.en_passant_square = Some(square - 8);
                 }
-                Color::Black if square >= 32 && square < 40 => {
+                Color::Black if (32..40).contains(&square) => {
                     self.en_passant_square = Some(square + 8);
                 }
                 _ => self.en_passant_square = None,
@@ -250,6 +1330,7 @@ impl Board {
         // Clear all bits for this piece type and set the new position
         *bitboard = Bitboard::empty();
         bitboard.set_bit(square);
+        self.refresh_occupancy();
     }
 
     /// Gets all legal moves for a piece at the given square
@@ -265,12 +1346,119 @@ impl Board {
                     PieceType::Queen => self.get_queen_moves(square, color),
                 };
                 // Remove moves that would capture own pieces
-                moves & !self.get_color_pieces(color)
+                let mut moves = moves & !self.get_color_pieces(color);
+                // A pinned piece may only move along the line to its king
+                if self.pinned(color).test_bit(square) {
+                    if let Some(king) = self.king_square(color) {
+                        moves = moves & Bitboard::from_u64(line(king, square));
+                    }
+                }
+                moves
             }
             None => Bitboard::empty(),
         }
     }
 
+    /// Returns true if the position occurred before since the last capture or pawn move,
+    /// counting only the moves played on this board
+    pub fn is_repetition(&self) -> bool {
+        let hash = self.hash();
+        self.history
+            .iter()
+            .rev()
+            .take(self.halfmove_clock as usize)
+            .skip(1)
+            .step_by(2)
+            .any(|undo| undo.hash == hash)
+    }
+
+    /// Returns true if neither side has enough material to deliver checkmate
+    /// (K vs K, K+minor vs K, or only same-colored bishops besides the kings)
+    pub fn is_insufficient_material(&self) -> bool {
+        let heavy = self.white_pawns
+            | self.black_pawns
+            | self.white_rooks
+            | self.black_rooks
+            | self.white_queens
+            | self.black_queens;
+        if heavy.as_u64() != 0 {
+            return false;
+        }
+
+        let knights = self.white_knights | self.black_knights;
+        let bishops = self.white_bishops | self.black_bishops;
+        if (knights | bishops).pop_count() <= 1 {
+            return true;
+        }
+
+        // Any number of bishops is harmless if they all live on one color complex
+        knights.as_u64() == 0
+            && (bishops.as_u64() & LIGHT_SQUARES == 0 || bishops.as_u64() & DARK_SQUARES == 0)
+    }
+
+    /// Returns true if no sequence of legal moves can lead to checkmate, either
+    /// through insufficient material or a fully locked pawn structure
+    pub fn is_dead_position(&self) -> bool {
+        self.is_insufficient_material() || self.is_locked_pawn_position()
+    }
+
+    /// Detects kings-and-pawns positions where every pawn is blocked by an enemy
+    /// pawn, no pawn can capture, and neither king can reach an undefended enemy pawn
+    fn is_locked_pawn_position(&self) -> bool {
+        let white_pawns = self.white_pawns.as_u64();
+        let black_pawns = self.black_pawns.as_u64();
+        let kings = (self.white_king | self.black_king).as_u64();
+        let pawns = white_pawns | black_pawns;
+
+        if self.get_all_pieces().as_u64() != pawns | kings {
+            return false;
+        }
+
+        // Every pawn must be blocked head-on by an enemy pawn
+        if (white_pawns << 8) & !black_pawns != 0 || (black_pawns >> 8) & !white_pawns != 0 {
+            return false;
+        }
+
+        let white_attacks = ((white_pawns << 7) & !FILE_H) | ((white_pawns << 9) & !FILE_A);
+        let black_attacks = ((black_pawns >> 9) & !FILE_H) | ((black_pawns >> 7) & !FILE_A);
+        if white_attacks & black_pawns != 0 || black_attacks & white_pawns != 0 {
+            return false;
+        }
+
+        let sides = [
+            (self.white_king.as_u64(), black_pawns, black_attacks),
+            (self.black_king.as_u64(), white_pawns, white_attacks),
+        ];
+        for (king, enemy_pawns, enemy_attacks) in sides {
+            // Flood fill the squares the king can ever walk to
+            let passable = !(pawns | enemy_attacks);
+            let mut region = king;
+            loop {
+                let next = (region | Self::king_neighbourhood(region)) & (passable | king);
+                if next == region {
+                    break;
+                }
+                region = next;
+            }
+
+            if Self::king_neighbourhood(region) & enemy_pawns & !enemy_attacks != 0 {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn king_neighbourhood(squares: u64) -> u64 {
+        let mut neighbourhood = 0;
+        let mut remaining = Bitboard::from_u64(squares);
+        while let Some(sq) = remaining.lsb() {
+            neighbourhood |= KING_MOVES[sq as usize];
+            remaining.clear_bit(sq);
+        }
+        neighbourhood
+    }
+
     fn get_knight_moves(&self, square: u8, _color: Color) -> Bitboard {
         Bitboard::from_u64(KNIGHT_MOVES[square as usize])
     }
@@ -297,22 +1485,17 @@ impl Board {
                         }
                     }
                     // Regular captures
-                    if square % 8 != 0 && square < 57 && enemy_pieces.test_bit(square + 7) {
+                    if !square.is_multiple_of(8) && square < 57 && enemy_pieces.test_bit(square + 7)
+                    {
                         moves.set_bit(square + 7);
                     }
                     if square % 8 != 7 && square < 55 && enemy_pieces.test_bit(square + 9) {
                         moves.set_bit(square + 9);
                     }
-                    // En passant captures
+                    // En passant captures onto the square behind the double-pushed pawn
                     if let Some(ep_square) = self.en_passant_square {
-                        if square >= 32 && square < 40 {
-                            // White pawns on rank 5
-                            if square % 8 != 0 && ep_square == square - 1 {
-                                moves.set_bit(ep_square);
-                            }
-                            if square % 8 != 7 && ep_square == square + 1 {
-                                moves.set_bit(ep_square);
-                            }
+                        if pawn_attacks(color, square).test_bit(ep_square) {
+                            moves.set_bit(ep_square);
                         }
                     }
                 }
@@ -329,22 +1512,17 @@ impl Board {
                         }
                     }
                     // Regular captures
-                    if square % 8 != 0 && square >= 9 && enemy_pieces.test_bit(square - 9) {
+                    if !square.is_multiple_of(8) && square >= 9 && enemy_pieces.test_bit(square - 9)
+                    {
                         moves.set_bit(square - 9);
                     }
                     if square % 8 != 7 && square >= 7 && enemy_pieces.test_bit(square - 7) {
                         moves.set_bit(square - 7);
                     }
-                    // En passant captures
+                    // En passant captures onto the square behind the double-pushed pawn
                     if let Some(ep_square) = self.en_passant_square {
-                        if square >= 24 && square < 32 {
-                            // Black pawns on rank 4
-                            if square % 8 != 0 && ep_square == square - 1 {
-                                moves.set_bit(ep_square);
-                            }
-                            if square % 8 != 7 && ep_square == square + 1 {
-                                moves.set_bit(ep_square);
-                            }
+                        if pawn_attacks(color, square).test_bit(ep_square) {
+                            moves.set_bit(ep_square);
                         }
                     }
                 }
@@ -353,111 +1531,16 @@ impl Board {
         moves
     }
 
-    fn get_bishop_moves(&self, square: u8, color: Color) -> Bitboard {
-        let mut moves = Bitboard::empty();
-        let all_pieces = self.get_all_pieces();
-        let enemy_pieces = self.get_color_pieces(color.opposite());
-
-        // Northeast
-        self.add_diagonal_moves(&mut moves, square, true, true, all_pieces, enemy_pieces);
-        // Southeast
-        self.add_diagonal_moves(&mut moves, square, false, true, all_pieces, enemy_pieces);
-        // Southwest
-        self.add_diagonal_moves(&mut moves, square, false, false, all_pieces, enemy_pieces);
-        // Northwest
-        self.add_diagonal_moves(&mut moves, square, true, false, all_pieces, enemy_pieces);
-
-        moves
+    fn get_bishop_moves(&self, square: u8, _color: Color) -> Bitboard {
+        bishop_attacks(square, self.get_all_pieces())
     }
 
-    fn get_rook_moves(&self, square: u8, color: Color) -> Bitboard {
-        let mut moves = Bitboard::empty();
-        let all_pieces = self.get_all_pieces();
-        let enemy_pieces = self.get_color_pieces(color.opposite());
-
-        // Get all possible moves from precomputed rays
-        let north = Bitboard::from_u64(NORTH_RAY[square as usize]);
-        let south = Bitboard::from_u64(SOUTH_RAY[square as usize]);
-        let east = Bitboard::from_u64(EAST_RAY[square as usize]);
-        let west = Bitboard::from_u64(WEST_RAY[square as usize]);
-
-        // For each direction, find the first piece (if any) and adjust moves accordingly
-        moves = moves | self.get_ray_moves(north, all_pieces, enemy_pieces);
-        moves = moves | self.get_ray_moves(south, all_pieces, enemy_pieces);
-        moves = moves | self.get_ray_moves(east, all_pieces, enemy_pieces);
-        moves = moves | self.get_ray_moves(west, all_pieces, enemy_pieces);
-
-        moves
-    }
-
-    fn get_queen_moves(&self, square: u8, color: Color) -> Bitboard {
-        self.get_bishop_moves(square, color) | self.get_rook_moves(square, color)
-    }
-
-    fn get_ray_moves(
-        &self,
-        ray: Bitboard,
-        all_pieces: Bitboard,
-        enemy_pieces: Bitboard,
-    ) -> Bitboard {
-        let mut moves = Bitboard::empty();
-        let mut current_ray = ray;
-
-        // Process squares in the ray until we hit a piece
-        while let Some(sq) = current_ray.lsb() {
-            if all_pieces.test_bit(sq) {
-                // If it's an enemy piece, include it as a capture
-                if enemy_pieces.test_bit(sq) {
-                    moves.set_bit(sq);
-                }
-                break;
-            }
-            moves.set_bit(sq);
-            current_ray.clear_bit(sq);
-        }
-
-        moves
+    fn get_rook_moves(&self, square: u8, _color: Color) -> Bitboard {
+        rook_attacks(square, self.get_all_pieces())
     }
 
-    fn add_diagonal_moves(
-        &self,
-        moves: &mut Bitboard,
-        square: u8,
-        up: bool,
-        right: bool,
-        all_pieces: Bitboard,
-        enemy_pieces: Bitboard,
-    ) {
-        let mut current_square = square;
-        let delta_rank: i8 = if up { 1 } else { -1 };
-        let delta_file: i8 = if right { 1 } else { -1 };
-
-        loop {
-            let rank = (current_square / 8) as i8;
-            let file = (current_square % 8) as i8;
-
-            let new_rank = rank + delta_rank;
-            let new_file = file + delta_file;
-
-            // Check board boundaries
-            if new_rank < 0 || new_rank > 7 || new_file < 0 || new_file > 7 {
-                break;
-            }
-
-            let new_square = (new_rank * 8 + new_file) as u8;
-            moves.set_bit(new_square);
-
-            // Stop if we hit any piece
-            if all_pieces.test_bit(new_square) {
-                // If it's not an enemy piece, remove this square from valid moves
-                if !enemy_pieces.test_bit(new_square) {
-                    moves.clear_bit(new_square);
-                }
-                break;
-            }
-
-            current_square = new_square;
-        }
+    fn get_queen_moves(&self, square: u8, _color: Color) -> Bitboard {
+        queen_attacks(square, self.get_all_pieces())
     }
 }
 
@@ -468,25 +1551,11 @@ impl std::fmt::Display for Board {
             let mut line = String::new();
             for file in 0..8 {
                 let square = rank * 8 + file;
-                let symbol = if let Some((piece, color)) = self.get_piece_at(square) {
-                    match (piece, color) {
-                        (PieceType::Pawn, Color::White) => "P",
-                        (PieceType::Knight, Color::White) => "N",
-                        (PieceType::Bishop, Color::White) => "B",
-                        (PieceType::Rook, Color::White) => "R",
-                        (PieceType::Queen, Color::White) => "Q",
-                        (PieceType::King, Color::White) => "K",
-                        (PieceType::Pawn, Color::Black) => "p",
-                        (PieceType::Knight, Color::Black) => "n",
-                        (PieceType::Bishop, Color::Black) => "b",
-                        (PieceType::Rook, Color::Black) => "r",
-                        (PieceType::Queen, Color::Black) => "q",
-                        (PieceType::King, Color::Black) => "k",
-                    }
-                } else {
-                    "."
+                let symbol = match self.get_piece_at(square) {
+                    Some((piece, color)) => piece_char(piece, color),
+                    None => '.',
                 };
-                line.push_str(symbol);
+                line.push(symbol);
                 if file < 7 {
                     line.push(' ');
                 }
@@ -496,3 +1565,56 @@ impl std::fmt::Display for Board {
         Ok(())
     }
 }
+
+/// Gets the FEN character for a piece, uppercase for White and lowercase for Black
+pub fn piece_char(piece_type: PieceType, color: Color) -> char {
+    let c = match piece_type {
+        PieceType::Pawn => 'p',
+        PieceType::Knight => 'n',
+        PieceType::Bishop => 'b',
+        PieceType::Rook => 'r',
+        PieceType::Queen => 'q',
+        PieceType::King => 'k',
+    };
+    match color {
+        Color::White => c.to_ascii_uppercase(),
+        Color::Black => c,
+    }
+}
+
+/// Parses a FEN piece character, uppercase for White and lowercase for Black
+pub fn piece_from_char(c: char) -> Option<(PieceType, Color)> {
+    let piece_type = match c.to_ascii_lowercase() {
+        'p' => PieceType::Pawn,
+        'n' => PieceType::Knight,
+        'b' => PieceType::Bishop,
+        'r' => PieceType::Rook,
+        'q' => PieceType::Queen,
+        'k' => PieceType::King,
+        _ => return None,
+    };
+    let color = if c.is_ascii_uppercase() {
+        Color::White
+    } else {
+        Color::Black
+    };
+    Some((piece_type, color))
+}
+
+fn castling_flag(color: Color, kingside: bool) -> u8 {
+    match (color, kingside) {
+        (Color::White, true) => WHITE_KINGSIDE,
+        (Color::White, false) => WHITE_QUEENSIDE,
+        (Color::Black, true) => BLACK_KINGSIDE,
+        (Color::Black, false) => BLACK_QUEENSIDE,
+    }
+}
+
+// Square of the piece captured by a move, which differs from the destination for en passant
+fn capture_square(mv: Move, us: Color) -> u8 {
+    match (mv.kind(), us) {
+        (MoveKind::EnPassant, Color::White) => mv.to() - 8,
+        (MoveKind::EnPassant, Color::Black) => mv.to() + 8,
+        _ => mv.to(),
+    }
+}
@@ -10,7 +10,7 @@ pub const FILE_H: u64 = FILE_A << 7;
 
 // Rank masks (horizontal strips)
 pub const RANK_1: u64 = 0x00000000000000FF;
-pub const RANK_2: u64 = RANK_1 << (8 * 1);
+pub const RANK_2: u64 = RANK_1 << 8;
 pub const RANK_3: u64 = RANK_1 << (8 * 2);
 pub const RANK_4: u64 = RANK_1 << (8 * 3);
 pub const RANK_5: u64 = RANK_1 << (8 * 4);
@@ -21,6 +21,8 @@ pub const RANK_8: u64 = RANK_1 << (8 * 7);
 // Special square combinations
 pub const CENTER_SQUARES: u64 = 0x0000001818000000; // e4, d4, e5, d5
 pub const EXTENDED_CENTER: u64 = 0x00003C3C3C3C0000; // 16 central squares
+pub const LIGHT_SQUARES: u64 = 0x55AA55AA55AA55AA; // Same color as h1
+pub const DARK_SQUARES: u64 = !LIGHT_SQUARES;
 
 // Initial piece positions
 pub const WHITE_PAWNS_INIT: u64 = RANK_2;
@@ -40,6 +42,12 @@ pub const BLACK_KING_INIT: u64 = 0x1000000000000000;
 pub const KINGSIDE_SQUARES: u64 = 0x0000000000000060; // f1, g1 for white
 pub const QUEENSIDE_SQUARES: u64 = 0x000000000000000E; // b1, c1, d1 for white
 
+// Search bounds, shared by the search and the transposition table
+/// Score of delivering mate on the current move; mate in n plies scores `MATE - n`
+pub const MATE: i32 = 32000;
+/// Deepest ply the search will reach, including quiescence
+pub const MAX_PLY: usize = 128;
+
 // Move pattern masks
 pub const KNIGHT_MOVES: [u64; 64] = generate_knight_moves();
 pub const KING_MOVES: [u64; 64] = generate_king_moves();
@@ -47,6 +55,40 @@ pub const NORTH_RAY: [u64; 64] = generate_ray_moves(8);
 pub const SOUTH_RAY: [u64; 64] = generate_ray_moves(-8);
 pub const EAST_RAY: [u64; 64] = generate_ray_moves(1);
 pub const WEST_RAY: [u64; 64] = generate_ray_moves(-1);
+pub const NORTH_EAST_RAY: [u64; 64] = generate_diagonal_ray_moves(1, 1);
+pub const NORTH_WEST_RAY: [u64; 64] = generate_diagonal_ray_moves(1, -1);
+pub const SOUTH_EAST_RAY: [u64; 64] = generate_diagonal_ray_moves(-1, 1);
+pub const SOUTH_WEST_RAY: [u64; 64] = generate_diagonal_ray_moves(-1, -1);
+pub const WHITE_PAWN_ATTACKS: [u64; 64] = generate_pawn_attacks(true);
+pub const BLACK_PAWN_ATTACKS: [u64; 64] = generate_pawn_attacks(false);
+
+// Square pair lookups, indexed by [from][to]
+pub static BETWEEN: [[u64; 64]; 64] = generate_between();
+pub static LINE: [[u64; 64]; 64] = generate_line();
+
+/// Gets the squares strictly between two squares on a shared rank, file or diagonal
+/// (empty if the squares are not aligned)
+pub fn between(a: u8, b: u8) -> u64 {
+    BETWEEN[a as usize][b as usize]
+}
+
+/// Gets the full rank, file or diagonal running through both squares, edge to edge
+/// (empty if the squares are not aligned)
+pub fn line(a: u8, b: u8) -> u64 {
+    LINE[a as usize][b as usize]
+}
+
+// Each ray paired with the ray pointing the opposite way
+const RAY_PAIRS: [([u64; 64], [u64; 64]); 8] = [
+    (NORTH_RAY, SOUTH_RAY),
+    (SOUTH_RAY, NORTH_RAY),
+    (EAST_RAY, WEST_RAY),
+    (WEST_RAY, EAST_RAY),
+    (NORTH_EAST_RAY, SOUTH_WEST_RAY),
+    (SOUTH_WEST_RAY, NORTH_EAST_RAY),
+    (NORTH_WEST_RAY, SOUTH_EAST_RAY),
+    (SOUTH_EAST_RAY, NORTH_WEST_RAY),
+];
 
 const fn generate_knight_moves() -> [u64; 64] {
     let mut moves = [0u64; 64];
@@ -164,3 +206,99 @@ const fn generate_king_moves() -> [u64; 64] {
     }
     moves
 }
+
+const fn generate_pawn_attacks(white: bool) -> [u64; 64] {
+    let mut attacks = [0u64; 64];
+    let mut square = 0;
+
+    while square < 64 {
+        let mut bb = 0u64;
+        let rank = square / 8;
+        let file = square % 8;
+
+        if white && rank < 7 {
+            if file > 0 {
+                bb |= 1u64 << (square + 7);
+            } // up left
+            if file < 7 {
+                bb |= 1u64 << (square + 9);
+            } // up right
+        }
+        if !white && rank > 0 {
+            if file > 0 {
+                bb |= 1u64 << (square - 9);
+            } // down left
+            if file < 7 {
+                bb |= 1u64 << (square - 7);
+            } // down right
+        }
+
+        attacks[square as usize] = bb;
+        square += 1;
+    }
+    attacks
+}
+
+const fn generate_diagonal_ray_moves(delta_rank: i8, delta_file: i8) -> [u64; 64] {
+    let mut moves = [0u64; 64];
+    let mut square = 0;
+
+    while square < 64 {
+        let mut bb = 0u64;
+        let mut rank = (square / 8) as i8 + delta_rank;
+        let mut file = (square % 8) as i8 + delta_file;
+
+        while rank >= 0 && rank < 8 && file >= 0 && file < 8 {
+            bb |= 1u64 << (rank * 8 + file);
+            rank += delta_rank;
+            file += delta_file;
+        }
+
+        moves[square as usize] = bb;
+        square += 1;
+    }
+    moves
+}
+
+const fn generate_between() -> [[u64; 64]; 64] {
+    let mut table = [[0u64; 64]; 64];
+    let mut from = 0;
+
+    while from < 64 {
+        let mut pair = 0;
+        while pair < RAY_PAIRS.len() {
+            let (ray, opposite) = RAY_PAIRS[pair];
+            let mut targets = ray[from];
+            while targets != 0 {
+                let to = targets.trailing_zeros() as usize;
+                table[from][to] = ray[from] & opposite[to];
+                targets &= targets - 1;
+            }
+            pair += 1;
+        }
+        from += 1;
+    }
+    table
+}
+
+const fn generate_line() -> [[u64; 64]; 64] {
+    let mut table = [[0u64; 64]; 64];
+    let mut from = 0;
+
+    while from < 64 {
+        let mut pair = 0;
+        while pair < RAY_PAIRS.len() {
+            let (ray, opposite) = RAY_PAIRS[pair];
+            let full = ray[from] | opposite[from] | (1u64 << from);
+            let mut targets = ray[from];
+            while targets != 0 {
+                let to = targets.trailing_zeros() as usize;
+                table[from][to] = full;
+                targets &= targets - 1;
+            }
+            pair += 1;
+        }
+        from += 1;
+    }
+    table
+}
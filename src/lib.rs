@@ -1,9 +1,25 @@
+pub mod attacks;
 pub mod bitboard;
 pub mod board;
 pub mod constants;
+pub mod eval;
+pub mod movegen;
+pub mod movepick;
+pub mod moves;
+pub mod pawns;
+pub mod search;
+pub mod see;
+pub mod timeman;
+pub mod tt;
+pub mod uci;
+pub mod variant;
+pub mod xboard;
+pub mod zobrist;
 
 // Re-export main types
 pub use bitboard::Bitboard;
 pub use board::Board;
 pub use board::Color;
 pub use board::PieceType;
+pub use moves::{Move, MoveKind, MoveList};
+pub use variant::{Outcome, Variant};
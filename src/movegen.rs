@@ -0,0 +1,460 @@
+use crate::attacks::*;
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Color, PieceType, PIECE_TYPES};
+use crate::constants::*;
+use crate::moves::{square_name, Move, MoveKind, MoveList};
+
+const PROMOTION_PIECES: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+// Which moves a generation stage produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Captures, // Captures, en passant and all promotions
+    Quiets,   // Everything else, including castling
+    All,
+}
+
+impl Board {
+    /// Appends all legal moves for the side to move to the list, following the board's variant
+    pub fn generate_moves(&self, list: &mut MoveList) {
+        self.variant().generate_moves(self, list);
+    }
+
+    /// Appends all moves that are legal in standard chess to the list
+    pub fn generate_standard_moves(&self, list: &mut MoveList) {
+        if self.is_in_check() {
+            self.generate_evasions(list);
+            return;
+        }
+        let start = list.len();
+        self.generate_pseudo_legal(list, Stage::All);
+        self.retain_legal(list, start);
+    }
+
+    /// Finds the legal move written in coordinate notation as `move_name` writes it, e.g.
+    /// "e2e4", "e7e8q" or "N@f3"
+    pub fn find_move(&self, text: &str) -> Option<Move> {
+        let mut moves = MoveList::new();
+        self.generate_moves(&mut moves);
+        moves.iter().copied().find(|&mv| self.move_name(mv) == text)
+    }
+
+    /// Writes a move of this game in coordinate notation. In Chess960 castling is written
+    /// as the king taking its own rook, e.g. "b1a1", since the king's destination may be
+    /// its origin or a square it could also step to; otherwise this is the move's Display.
+    pub fn move_name(&self, mv: Move) -> String {
+        match mv.kind() {
+            MoveKind::KingCastle | MoveKind::QueenCastle if self.is_chess960() => {
+                let color = if mv.from() < 8 {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+                let (rook, _) =
+                    self.castling_rook_squares(color, mv.kind() == MoveKind::KingCastle);
+                format!("{}{}", square_name(mv.from()), square_name(rook))
+            }
+            _ => mv.to_string(),
+        }
+    }
+
+    /// Appends legal captures, en passant captures and promotions to the list
+    pub fn generate_captures(&self, list: &mut MoveList) {
+        let start = list.len();
+        self.generate_pseudo_legal(list, Stage::Captures);
+        self.retain_legal(list, start);
+    }
+
+    /// Appends legal non-capturing, non-promoting moves (including castling) to the list
+    pub fn generate_quiets(&self, list: &mut MoveList) {
+        let start = list.len();
+        self.generate_pseudo_legal(list, Stage::Quiets);
+        self.retain_legal(list, start);
+    }
+
+    /// Appends legal quiet moves that give check to the list
+    pub fn generate_checks(&self, list: &mut MoveList) {
+        let start = list.len();
+        self.generate_quiets(list);
+        let mut i = start;
+        while i < list.len() {
+            if self.gives_check(list[i]) {
+                i += 1;
+            } else {
+                list.swap_remove(i);
+            }
+        }
+    }
+
+    /// Appends legal moves that get the side to move out of check to the list.
+    /// Nothing is generated if the side to move is not in check.
+    pub fn generate_evasions(&self, list: &mut MoveList) {
+        let checkers = self.checkers();
+        let us = self.side_to_move();
+        let king = match self.king_square(us) {
+            Some(king) if !checkers.is_empty() => king,
+            _ => return,
+        };
+
+        let start = list.len();
+        self.generate_king_moves(list, Stage::All);
+
+        // With a single checker, other pieces may capture it or block its line
+        if checkers.pop_count() == 1 {
+            let checker = checkers.lsb().unwrap();
+            let targets = checkers | Bitboard::from_u64(between(king, checker));
+            self.generate_pawn_moves(list, Stage::All, targets);
+            self.generate_piece_moves(list, Stage::All, targets);
+        }
+        self.retain_legal(list, start);
+    }
+
+    /// Appends legal drops of pocket pieces to the list. Pawns may not be dropped on the
+    /// first or last rank, and in check only drops that block it are legal.
+    pub fn generate_drops(&self, list: &mut MoveList) {
+        let us = self.side_to_move();
+        if !self.has_pocket_pieces(us) {
+            return;
+        }
+
+        let mut targets = !self.get_all_pieces();
+        let checkers = self.checkers();
+        if let (Some(checker), Some(king)) = (checkers.lsb(), self.king_square(us)) {
+            if checkers.pop_count() > 1 {
+                return;
+            }
+            targets = targets & Bitboard::from_u64(between(king, checker));
+        }
+
+        for &piece_type in PIECE_TYPES[..5].iter() {
+            if self.pocket(us, piece_type) == 0 {
+                continue;
+            }
+            let squares = match piece_type {
+                PieceType::Pawn => targets & Bitboard::from_u64(!(RANK_1 | RANK_8)),
+                _ => targets,
+            };
+            for square in squares.squares() {
+                list.push(Move::new(square, square, MoveKind::Drop(piece_type)));
+            }
+        }
+    }
+
+    /// Returns true if a pseudo-legal move does not leave the mover's own king in check
+    pub fn is_legal(&self, mv: Move) -> bool {
+        let us = self.side_to_move();
+        let king = match self.king_square(us) {
+            Some(king) => king,
+            None => return true,
+        };
+        let enemy = self.get_color_pieces(us.opposite());
+        let (from, to) = (mv.from(), mv.to());
+
+        if mv.is_castle() {
+            // The king may not start on, pass through or land on an attacked square. The
+            // castling rook is lifted too, so a slider it was shielding is seen.
+            let (rook_from, _) = self.castling_rook_squares(us, mv.kind() == MoveKind::KingCastle);
+            let mut occupied = self.get_all_pieces();
+            occupied.clear_bit(from);
+            occupied.clear_bit(rook_from);
+            return (from.min(to)..=from.max(to))
+                .all(|square| (self.attackers_to(square, occupied) & enemy).is_empty());
+        }
+
+        let mut occupied = self.get_all_pieces();
+        occupied.clear_bit(from);
+        occupied.set_bit(to);
+        let mut captured = Bitboard::empty();
+        captured.set_bit(to);
+        if mv.kind() == MoveKind::EnPassant {
+            let pawn_square = if us == Color::White { to - 8 } else { to + 8 };
+            occupied.clear_bit(pawn_square);
+            captured.set_bit(pawn_square);
+        }
+
+        let target = if from == king { to } else { king };
+        (self.attackers_to(target, occupied) & enemy & !captured).is_empty()
+    }
+
+    /// Returns true if the move, played by the side to move, checks the enemy king
+    pub fn gives_check(&self, mv: Move) -> bool {
+        let us = self.side_to_move();
+        let king = match self.king_square(us.opposite()) {
+            Some(king) => king,
+            None => return false,
+        };
+        let (from, to) = (mv.from(), mv.to());
+        let piece_type = match mv.promotion().or(mv.drop_piece()) {
+            Some(piece_type) => piece_type,
+            None => match self.get_piece_at(from) {
+                Some((piece_type, _)) => piece_type,
+                None => return false,
+            },
+        };
+
+        let mut occupied = self.get_all_pieces();
+        occupied.clear_bit(from);
+        let mut moved = Bitboard::empty();
+        moved.set_bit(from);
+        if mv.kind() == MoveKind::EnPassant {
+            occupied.clear_bit(if us == Color::White { to - 8 } else { to + 8 });
+        }
+
+        if mv.is_castle() {
+            let (rook_from, rook_to) =
+                self.castling_rook_squares(us, mv.kind() == MoveKind::KingCastle);
+            occupied.clear_bit(rook_from);
+            occupied.set_bit(to);
+            occupied.set_bit(rook_to);
+            if rook_attacks(rook_to, occupied).test_bit(king) {
+                return true;
+            }
+            moved.set_bit(rook_from);
+        } else {
+            occupied.set_bit(to);
+        }
+
+        let direct = match piece_type {
+            PieceType::Pawn => pawn_attacks(us, to),
+            PieceType::Knight => knight_attacks(to),
+            PieceType::Bishop => bishop_attacks(to, occupied),
+            PieceType::Rook => rook_attacks(to, occupied),
+            PieceType::Queen => queen_attacks(to, occupied),
+            PieceType::King => Bitboard::empty(),
+        };
+        if direct.test_bit(king) {
+            return true;
+        }
+
+        // Discovered checks from sliders behind the moved piece
+        let queens = self.get_pieces(PieceType::Queen, us);
+        let diagonal = (self.get_pieces(PieceType::Bishop, us) | queens) & !moved;
+        let orthogonal = (self.get_pieces(PieceType::Rook, us) | queens) & !moved;
+        !((bishop_attacks(king, occupied) & diagonal) | (rook_attacks(king, occupied) & orthogonal))
+            .is_empty()
+    }
+
+    /// Counts the leaf nodes of the legal move tree to the given depth
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut list = MoveList::new();
+        self.generate_moves(&mut list);
+        if depth == 1 {
+            return list.len() as u64;
+        }
+
+        let mut nodes = 0;
+        for &mv in list.iter() {
+            self.make_move(mv);
+            nodes += self.perft(depth - 1);
+            self.unmake_move(mv);
+        }
+        nodes
+    }
+
+    /// Appends pseudo-legal moves to the list: moves that follow the piece movement and
+    /// castling rules but may leave the mover's king in check. Variants with their own
+    /// legality rules filter these.
+    pub fn generate_pseudo_legal_moves(&self, list: &mut MoveList) {
+        self.generate_pseudo_legal(list, Stage::All);
+    }
+
+    fn generate_pseudo_legal(&self, list: &mut MoveList, stage: Stage) {
+        self.generate_pawn_moves(list, stage, Bitboard::full());
+        self.generate_piece_moves(list, stage, Bitboard::full());
+        self.generate_king_moves(list, stage);
+    }
+
+    fn retain_legal(&self, list: &mut MoveList, start: usize) {
+        let us = self.side_to_move();
+        let king = self.king_square(us);
+        let pinned = self.pinned(us);
+        let in_check = self.is_in_check();
+
+        let mut i = start;
+        while i < list.len() {
+            let mv = list[i];
+            // Out of check, only king moves, en passant and pinned pieces can expose the king
+            let legal = match king {
+                Some(king)
+                    if !in_check && mv.from() != king && mv.kind() != MoveKind::EnPassant =>
+                {
+                    !pinned.test_bit(mv.from()) || line(king, mv.from()) & (1u64 << mv.to()) != 0
+                }
+                _ => self.is_legal(mv),
+            };
+            if legal {
+                i += 1;
+            } else {
+                list.swap_remove(i);
+            }
+        }
+    }
+
+    // Destinations allowed for a stage, before any target restriction
+    fn stage_destinations(&self, stage: Stage) -> Bitboard {
+        let us = self.side_to_move();
+        match stage {
+            Stage::Captures => self.get_color_pieces(us.opposite()),
+            Stage::Quiets => !self.get_all_pieces(),
+            Stage::All => !self.get_color_pieces(us),
+        }
+    }
+
+    fn generate_pawn_moves(&self, list: &mut MoveList, stage: Stage, targets: Bitboard) {
+        let us = self.side_to_move();
+        let enemy = self.get_color_pieces(us.opposite());
+        let occupied = self.get_all_pieces();
+        let (push, start_rank, promotion_rank) = match us {
+            Color::White => (8i8, RANK_2, RANK_8),
+            Color::Black => (-8i8, RANK_7, RANK_1),
+        };
+
+        for from in self.get_pieces(PieceType::Pawn, us).squares() {
+            // A pawn left on its last rank, as `place_piece` or a FEN allows, cannot move
+            let Some(one) = from.checked_add_signed(push).filter(|&square| square < 64) else {
+                continue;
+            };
+            let promotes = promotion_rank & (1u64 << one) != 0;
+
+            if !occupied.test_bit(one) {
+                if promotes {
+                    if stage != Stage::Quiets && targets.test_bit(one) {
+                        for piece in PROMOTION_PIECES {
+                            list.push(Move::new(from, one, MoveKind::Promotion(piece)));
+                        }
+                    }
+                } else if stage != Stage::Captures {
+                    if targets.test_bit(one) {
+                        list.push(Move::new(from, one, MoveKind::Quiet));
+                    }
+                    let two = (one as i8 + push) as u8;
+                    if start_rank & (1u64 << from) != 0
+                        && !occupied.test_bit(two)
+                        && targets.test_bit(two)
+                    {
+                        list.push(Move::new(from, two, MoveKind::DoublePawnPush));
+                    }
+                }
+            }
+
+            if stage == Stage::Quiets {
+                continue;
+            }
+
+            for to in (pawn_attacks(us, from) & enemy & targets).squares() {
+                if promotes {
+                    for piece in PROMOTION_PIECES {
+                        list.push(Move::new(from, to, MoveKind::PromotionCapture(piece)));
+                    }
+                } else {
+                    list.push(Move::new(from, to, MoveKind::Capture));
+                }
+            }
+
+            if let Some(ep_square) = self.en_passant_square() {
+                let captured = (ep_square as i8 - push) as u8;
+                if pawn_attacks(us, from).test_bit(ep_square)
+                    && (targets.test_bit(ep_square) || targets.test_bit(captured))
+                {
+                    list.push(Move::new(from, ep_square, MoveKind::EnPassant));
+                }
+            }
+        }
+    }
+
+    fn generate_piece_moves(&self, list: &mut MoveList, stage: Stage, targets: Bitboard) {
+        let us = self.side_to_move();
+        let enemy = self.get_color_pieces(us.opposite());
+        let occupied = self.get_all_pieces();
+        let destinations = self.stage_destinations(stage) & targets;
+
+        for piece_type in [
+            PieceType::Knight,
+            PieceType::Bishop,
+            PieceType::Rook,
+            PieceType::Queen,
+        ] {
+            for from in self.get_pieces(piece_type, us).squares() {
+                let attacks = match piece_type {
+                    PieceType::Knight => knight_attacks(from),
+                    PieceType::Bishop => bishop_attacks(from, occupied),
+                    PieceType::Rook => rook_attacks(from, occupied),
+                    _ => queen_attacks(from, occupied),
+                };
+                push_moves(list, from, attacks & destinations, enemy);
+            }
+        }
+    }
+
+    fn generate_king_moves(&self, list: &mut MoveList, stage: Stage) {
+        let us = self.side_to_move();
+        let them = us.opposite();
+        let from = match self.king_square(us) {
+            Some(square) => square,
+            None => return,
+        };
+        let enemy = self.get_color_pieces(them);
+        push_moves(
+            list,
+            from,
+            king_attacks(from) & self.stage_destinations(stage),
+            enemy,
+        );
+
+        if stage == Stage::Captures {
+            return;
+        }
+
+        // Castling: every square the king and rook cross or land on must be empty apart
+        // from the two castling pieces. Attacked squares are checked by is_legal.
+        let occupied = self.get_all_pieces().as_u64();
+        for kingside in [true, false] {
+            let rook_from = match self.castling_rook(us, kingside) {
+                Some(rook) => rook,
+                None => continue,
+            };
+            let (_, rook_to) = self.castling_rook_squares(us, kingside);
+            let king_to = (rook_from & 56) + if kingside { 6 } else { 2 };
+            if from & 56 != rook_from & 56
+                || (rook_from > from) != kingside
+                || !self.get_pieces(PieceType::Rook, us).test_bit(rook_from)
+            {
+                continue;
+            }
+
+            let path = between(from, king_to)
+                | between(rook_from, rook_to)
+                | (1u64 << king_to)
+                | (1u64 << rook_to);
+            let blockers = occupied & !(1u64 << from) & !(1u64 << rook_from);
+            if path & blockers == 0 {
+                let kind = if kingside {
+                    MoveKind::KingCastle
+                } else {
+                    MoveKind::QueenCastle
+                };
+                list.push(Move::new(from, king_to, kind));
+            }
+        }
+    }
+}
+
+fn push_moves(list: &mut MoveList, from: u8, destinations: Bitboard, enemy: Bitboard) {
+    for to in destinations.squares() {
+        let kind = if enemy.test_bit(to) {
+            MoveKind::Capture
+        } else {
+            MoveKind::Quiet
+        };
+        list.push(Move::new(from, to, kind));
+    }
+}
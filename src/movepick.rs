@@ -0,0 +1,378 @@
+use crate::board::{Board, Color, PieceType};
+use crate::constants::MAX_PLY;
+use crate::moves::{Move, MoveList, MAX_MOVES};
+use crate::see::see_value;
+
+/// Bound on the magnitude of butterfly history scores
+pub const MAX_HISTORY: i32 = 16384;
+
+// Largest history bonus a single cutoff can give
+const MAX_BONUS: i32 = 1536;
+
+/// Gets the history bonus for a cutoff at the given remaining depth
+pub fn history_bonus(depth: u32) -> i32 {
+    (32 * depth as i32 * depth as i32).min(MAX_BONUS)
+}
+
+/// Scores a capture or promotion by most valuable victim, then least valuable attacker
+pub fn mvv_lva(board: &Board, mv: Move) -> i32 {
+    let victim = if mv.is_capture() {
+        board
+            .get_piece_at(mv.to())
+            .map_or(see_value(PieceType::Pawn), |(piece, _)| see_value(piece))
+    } else {
+        0
+    };
+    let attacker = board
+        .get_piece_at(mv.from())
+        .map_or(0, |(piece, _)| piece as i32);
+    let promotion = mv.promotion().map_or(0, see_value);
+    (victim + promotion) * 8 - attacker
+}
+
+/// Move ordering statistics learned during a search: two killer moves per ply, a
+/// butterfly history of quiet moves by side, origin and destination, and the quiet move
+/// that last refuted each previous move. Each searching thread keeps its own.
+#[derive(Debug, Clone)]
+pub struct History {
+    killers: Vec<[Option<Move>; 2]>,
+    butterfly: Box<[[[i32; 64]; 64]; 2]>,
+    countermoves: Box<[[Option<Move>; 64]; 64]>,
+}
+
+impl History {
+    /// Creates empty tables
+    pub fn new() -> Self {
+        History {
+            killers: vec![[None; 2]; MAX_PLY],
+            butterfly: Box::new([[[0; 64]; 64]; 2]),
+            countermoves: Box::new([[None; 64]; 64]),
+        }
+    }
+
+    /// Forgets everything learned, e.g. between games
+    pub fn clear(&mut self) {
+        *self = History::new();
+    }
+
+    /// Forgets the killer moves, which only make sense within one search
+    pub fn clear_killers(&mut self) {
+        self.killers.fill([None; 2]);
+    }
+
+    /// Gets the killer moves at a ply, most recent first
+    pub fn killers(&self, ply: usize) -> [Option<Move>; 2] {
+        self.killers.get(ply).copied().unwrap_or([None; 2])
+    }
+
+    /// Records a quiet move that caused a cutoff at a ply
+    pub fn add_killer(&mut self, ply: usize, mv: Move) {
+        if let Some(killers) = self.killers.get_mut(ply) {
+            if killers[0] != Some(mv) {
+                killers[1] = killers[0];
+                killers[0] = Some(mv);
+            }
+        }
+    }
+
+    /// Gets the butterfly history score of a quiet move for the given side
+    pub fn butterfly(&self, color: Color, mv: Move) -> i32 {
+        self.butterfly[color as usize][mv.from() as usize][mv.to() as usize]
+    }
+
+    /// Adds a bonus (or a malus, if negative) to a quiet move's history. Scores decay
+    /// towards zero as they grow, so they stay within `MAX_HISTORY`.
+    pub fn update_butterfly(&mut self, color: Color, mv: Move, bonus: i32) {
+        let bonus = bonus.clamp(-MAX_HISTORY, MAX_HISTORY);
+        let entry = &mut self.butterfly[color as usize][mv.from() as usize][mv.to() as usize];
+        *entry += bonus - *entry * bonus.abs() / MAX_HISTORY;
+    }
+
+    /// Gets the quiet move that last refuted the previous move
+    pub fn countermove(&self, previous: Move) -> Option<Move> {
+        self.countermoves[previous.from() as usize][previous.to() as usize]
+    }
+
+    /// Records the quiet move that refuted the previous move
+    pub fn set_countermove(&mut self, previous: Move, mv: Move) {
+        self.countermoves[previous.from() as usize][previous.to() as usize] = Some(mv);
+    }
+
+    /// Updates every table after a quiet move caused a cutoff: it becomes a killer and
+    /// the countermove, and gains history while the quiet moves tried before it lose it
+    pub fn update_quiet_cutoff(
+        &mut self,
+        color: Color,
+        ply: usize,
+        previous: Option<Move>,
+        mv: Move,
+        depth: u32,
+        tried: &[Move],
+    ) {
+        let bonus = history_bonus(depth);
+        self.add_killer(ply, mv);
+        if let Some(previous) = previous {
+            self.set_countermove(previous, mv);
+        }
+        self.update_butterfly(color, mv, bonus);
+        for &quiet in tried.iter().filter(|&&quiet| quiet != mv) {
+            self.update_butterfly(color, quiet, -bonus);
+        }
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// What the picker returns next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    HashMove,
+    GoodCaptures,
+    FirstKiller,
+    SecondKiller,
+    Countermove,
+    Quiets,
+    BadCaptures,
+    Done,
+}
+
+/// Returns the legal moves of a position one at a time, best first: the hash move,
+/// captures and promotions that do not lose material by MVV-LVA, the killer moves, the
+/// countermove, the other quiet moves by history, and finally the losing captures.
+/// Moves are generated by stage where the variant allows it and only sorted as far as
+/// they are taken, so a cutoff on an early move skips most of the work.
+pub struct MovePicker {
+    stage: Stage,
+    captures_only: bool,
+    staged: bool,
+    hash_move: Option<Move>,
+    killers: [Option<Move>; 2],
+    countermove: Option<Move>,
+    moves: MoveList,
+    scores: [i32; MAX_MOVES],
+    // Captures are stored first, losing ones moved to the front as they are found, and
+    // quiets follow them
+    current: usize,
+    end_bad_captures: usize,
+    end_captures: usize,
+    captures_generated: bool,
+    quiets_generated: bool,
+}
+
+impl MovePicker {
+    /// Creates a picker for all legal moves. The hash move is returned first if it is
+    /// legal, and the killers and countermove for the ply and previous move are taken
+    /// from the history.
+    pub fn new(
+        board: &Board,
+        hash_move: Option<Move>,
+        history: &History,
+        ply: usize,
+        previous: Option<Move>,
+    ) -> Self {
+        let mut picker = Self::with_stage(board, false);
+        picker.killers = history.killers(ply);
+        picker.countermove = previous.and_then(|previous| history.countermove(previous));
+        picker.set_hash_move(board, hash_move);
+        picker
+    }
+
+    /// Creates a picker for legal captures and promotions only, e.g. for a quiescence
+    /// search
+    pub fn captures(board: &Board, hash_move: Option<Move>) -> Self {
+        let mut picker = Self::with_stage(board, true);
+        let hash_move = hash_move.filter(|mv| mv.is_capture() || mv.is_promotion());
+        picker.set_hash_move(board, hash_move);
+        picker
+    }
+
+    fn with_stage(board: &Board, captures_only: bool) -> Self {
+        MovePicker {
+            stage: Stage::HashMove,
+            captures_only,
+            staged: board.variant().has_standard_moves() && !board.is_in_check(),
+            hash_move: None,
+            killers: [None; 2],
+            countermove: None,
+            moves: MoveList::new(),
+            scores: [0; MAX_MOVES],
+            current: 0,
+            end_bad_captures: 0,
+            end_captures: 0,
+            captures_generated: false,
+            quiets_generated: false,
+        }
+    }
+
+    // Keeps the hash move only if it is legal, generating the stage it belongs to early
+    fn set_hash_move(&mut self, board: &Board, hash_move: Option<Move>) {
+        if let Some(mv) = hash_move {
+            self.generate_captures(board);
+            let legal = if mv.is_capture() || mv.is_promotion() {
+                self.moves.as_slice()[..self.end_captures].contains(&mv)
+            } else {
+                self.generate_quiets(board);
+                self.moves.as_slice()[self.end_captures..].contains(&mv)
+            };
+            if legal {
+                self.hash_move = Some(mv);
+            }
+        }
+    }
+
+    /// Gets the next move, or None once every move has been returned. The board and
+    /// history must be the ones the picker was created with, in the same position.
+    pub fn next(&mut self, board: &Board, history: &History) -> Option<Move> {
+        loop {
+            match self.stage {
+                Stage::HashMove => {
+                    self.stage = Stage::GoodCaptures;
+                    self.generate_captures(board);
+                    self.current = 0;
+                    if self.hash_move.is_some() {
+                        return self.hash_move;
+                    }
+                }
+                Stage::GoodCaptures => {
+                    if self.current == self.end_captures {
+                        if self.captures_only {
+                            self.stage = Stage::BadCaptures;
+                            self.current = 0;
+                        } else {
+                            self.stage = Stage::FirstKiller;
+                            self.generate_quiets(board);
+                            self.score_quiets(board, history);
+                        }
+                        continue;
+                    }
+                    let index = self.pick_best(self.end_captures);
+                    let mv = self.moves[index];
+                    if Some(mv) == self.hash_move {
+                        continue;
+                    }
+                    if self.staged && !board.see_ge(mv, 0) {
+                        self.moves.swap(self.end_bad_captures, index);
+                        self.scores.swap(self.end_bad_captures, index);
+                        self.end_bad_captures += 1;
+                        continue;
+                    }
+                    return Some(mv);
+                }
+                Stage::FirstKiller | Stage::SecondKiller | Stage::Countermove => {
+                    let mv = match self.stage {
+                        Stage::FirstKiller => self.killers[0],
+                        Stage::SecondKiller => self.killers[1],
+                        _ => self
+                            .countermove
+                            .filter(|&mv| !self.killers.contains(&Some(mv))),
+                    };
+                    self.stage = match self.stage {
+                        Stage::FirstKiller => Stage::SecondKiller,
+                        Stage::SecondKiller => Stage::Countermove,
+                        _ => {
+                            self.current = self.end_captures;
+                            Stage::Quiets
+                        }
+                    };
+                    if let Some(mv) = mv.filter(|&mv| self.is_special_quiet(mv)) {
+                        return Some(mv);
+                    }
+                }
+                Stage::Quiets => {
+                    if self.current == self.moves.len() {
+                        self.stage = Stage::BadCaptures;
+                        self.current = 0;
+                        continue;
+                    }
+                    let index = self.pick_best(self.moves.len());
+                    let mv = self.moves[index];
+                    if Some(mv) == self.hash_move
+                        || self.killers.contains(&Some(mv))
+                        || Some(mv) == self.countermove
+                    {
+                        continue;
+                    }
+                    return Some(mv);
+                }
+                Stage::BadCaptures => {
+                    if self.current == self.end_bad_captures {
+                        self.stage = Stage::Done;
+                        continue;
+                    }
+                    let mv = self.moves[self.current];
+                    self.current += 1;
+                    if Some(mv) != self.hash_move {
+                        return Some(mv);
+                    }
+                }
+                Stage::Done => return None,
+            }
+        }
+    }
+
+    // Returns true if a killer or countermove is a legal quiet move not returned already
+    fn is_special_quiet(&self, mv: Move) -> bool {
+        Some(mv) != self.hash_move && self.moves.as_slice()[self.end_captures..].contains(&mv)
+    }
+
+    // Moves the best scoring move left before `end` to the current index and returns
+    // that index
+    fn pick_best(&mut self, end: usize) -> usize {
+        let mut best = self.current;
+        for i in self.current + 1..end {
+            if self.scores[i] > self.scores[best] {
+                best = i;
+            }
+        }
+        self.moves.swap(self.current, best);
+        self.scores.swap(self.current, best);
+        self.current += 1;
+        self.current - 1
+    }
+
+    fn generate_captures(&mut self, board: &Board) {
+        if self.captures_generated {
+            return;
+        }
+        self.captures_generated = true;
+
+        if self.staged {
+            board.generate_captures(&mut self.moves);
+            self.end_captures = self.moves.len();
+        } else {
+            // Everything at once, with captures and promotions partitioned to the front
+            board.generate_moves(&mut self.moves);
+            for i in 0..self.moves.len() {
+                if self.moves[i].is_capture() || self.moves[i].is_promotion() {
+                    self.moves.swap(self.end_captures, i);
+                    self.end_captures += 1;
+                }
+            }
+            while self.captures_only && self.moves.len() > self.end_captures {
+                self.moves.swap_remove(self.moves.len() - 1);
+            }
+            self.quiets_generated = true;
+        }
+        for i in 0..self.end_captures {
+            self.scores[i] = mvv_lva(board, self.moves[i]);
+        }
+    }
+
+    fn generate_quiets(&mut self, board: &Board) {
+        if !self.quiets_generated && !self.captures_only {
+            board.generate_quiets(&mut self.moves);
+        }
+        self.quiets_generated = true;
+    }
+
+    fn score_quiets(&mut self, board: &Board, history: &History) {
+        let color = board.side_to_move();
+        for i in self.end_captures..self.moves.len() {
+            self.scores[i] = history.butterfly(color, self.moves[i]);
+        }
+    }
+}
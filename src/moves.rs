@@ -0,0 +1,267 @@
+use crate::board::{piece_char, Color, PieceType, PIECE_TYPES};
+
+/// Maximum number of moves a `MoveList` can hold (no legal position exceeds 218 in
+/// standard chess, but crazyhouse drops can add a few hundred more)
+pub const MAX_MOVES: usize = 512;
+
+/// Distinguishes moves that need special handling when applied to a board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    Quiet,
+    DoublePawnPush,
+    KingCastle,
+    QueenCastle,
+    Capture,
+    EnPassant,
+    Promotion(PieceType),
+    PromotionCapture(PieceType),
+    Drop(PieceType),
+}
+
+/// A move from one square to another. Castling moves are encoded as the king's move,
+/// and drops have the same origin and destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    from: u8,
+    to: u8,
+    kind: MoveKind,
+}
+
+impl Move {
+    /// Creates a new move
+    pub fn new(from: u8, to: u8, kind: MoveKind) -> Self {
+        debug_assert!(from < 64 && to < 64, "squares must be in range 0-63");
+        Move { from, to, kind }
+    }
+
+    /// Gets the origin square
+    pub fn from(&self) -> u8 {
+        self.from
+    }
+
+    /// Gets the destination square
+    pub fn to(&self) -> u8 {
+        self.to
+    }
+
+    /// Gets the kind of move
+    pub fn kind(&self) -> MoveKind {
+        self.kind
+    }
+
+    /// Returns true if the move captures a piece (including en passant)
+    pub fn is_capture(&self) -> bool {
+        matches!(
+            self.kind,
+            MoveKind::Capture | MoveKind::EnPassant | MoveKind::PromotionCapture(_)
+        )
+    }
+
+    /// Returns true if the move is a pawn promotion
+    pub fn is_promotion(&self) -> bool {
+        self.promotion().is_some()
+    }
+
+    /// Gets the piece a pawn promotes to, if any
+    pub fn promotion(&self) -> Option<PieceType> {
+        match self.kind {
+            MoveKind::Promotion(piece) | MoveKind::PromotionCapture(piece) => Some(piece),
+            _ => None,
+        }
+    }
+
+    /// Gets the piece placed from the pocket by a drop, if any
+    pub fn drop_piece(&self) -> Option<PieceType> {
+        match self.kind {
+            MoveKind::Drop(piece) => Some(piece),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the move drops a piece from the pocket
+    pub fn is_drop(&self) -> bool {
+        self.drop_piece().is_some()
+    }
+
+    /// Returns true if the move is a castling move
+    pub fn is_castle(&self) -> bool {
+        matches!(self.kind, MoveKind::KingCastle | MoveKind::QueenCastle)
+    }
+
+    /// Packs the move into 17 bits: origin, destination and a 5-bit kind code
+    pub fn to_bits(&self) -> u32 {
+        let kind = match self.kind {
+            MoveKind::Quiet => 0,
+            MoveKind::DoublePawnPush => 1,
+            MoveKind::KingCastle => 2,
+            MoveKind::QueenCastle => 3,
+            MoveKind::Capture => 4,
+            MoveKind::EnPassant => 5,
+            MoveKind::Promotion(piece) => 6 + piece as u32,
+            MoveKind::PromotionCapture(piece) => 12 + piece as u32,
+            MoveKind::Drop(piece) => 18 + piece as u32,
+        };
+        self.from as u32 | (self.to as u32) << 6 | kind << 12
+    }
+
+    /// Unpacks a move packed with `to_bits`, or None if the bits are not a valid move
+    pub fn from_bits(bits: u32) -> Option<Move> {
+        let from = (bits & 63) as u8;
+        let to = (bits >> 6 & 63) as u8;
+        let kind = match bits >> 12 {
+            0 => MoveKind::Quiet,
+            1 => MoveKind::DoublePawnPush,
+            2 => MoveKind::KingCastle,
+            3 => MoveKind::QueenCastle,
+            4 => MoveKind::Capture,
+            5 => MoveKind::EnPassant,
+            code @ 6..=11 => MoveKind::Promotion(PIECE_TYPES[code as usize - 6]),
+            code @ 12..=17 => MoveKind::PromotionCapture(PIECE_TYPES[code as usize - 12]),
+            code @ 18..=23 => MoveKind::Drop(PIECE_TYPES[code as usize - 18]),
+            _ => return None,
+        };
+        Some(Move::new(from, to, kind))
+    }
+}
+
+impl std::fmt::Display for Move {
+    /// Formats the move in coordinate notation, e.g. "e2e4", "e7e8q" or "N@f3"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(piece) = self.drop_piece() {
+            let c = piece_char(piece, Color::White);
+            return write!(f, "{}@{}", c, square_name(self.to));
+        }
+        write!(f, "{}{}", square_name(self.from), square_name(self.to))?;
+        match self.promotion() {
+            Some(PieceType::Knight) => write!(f, "n"),
+            Some(PieceType::Bishop) => write!(f, "b"),
+            Some(PieceType::Rook) => write!(f, "r"),
+            Some(PieceType::Queen) => write!(f, "q"),
+            Some(PieceType::King) => write!(f, "k"),
+            Some(PieceType::Pawn) | None => Ok(()),
+        }
+    }
+}
+
+/// A fixed-capacity, stack-allocated list of moves
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    /// Creates a new empty move list
+    pub fn new() -> Self {
+        MoveList {
+            moves: [Move::new(0, 0, MoveKind::Quiet); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    /// Appends a move to the list
+    pub fn push(&mut self, mv: Move) {
+        debug_assert!(self.len < MAX_MOVES, "move list overflow");
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    /// Removes all moves from the list
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Gets the number of moves in the list
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the list contains no moves
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if the list contains the given move
+    pub fn contains(&self, mv: &Move) -> bool {
+        self.as_slice().contains(mv)
+    }
+
+    /// Gets the moves as a slice
+    pub fn as_slice(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+
+    /// Gets the moves as a mutable slice, e.g. for sorting
+    pub fn as_mut_slice(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+
+    /// Returns an iterator over the moves
+    pub fn iter(&self) -> std::slice::Iter<'_, Move> {
+        self.as_slice().iter()
+    }
+
+    /// Removes the move at the given index, replacing it with the last move in the list
+    pub fn swap_remove(&mut self, index: usize) -> Move {
+        assert!(index < self.len, "move list index out of bounds");
+        let mv = self.moves[index];
+        self.len -= 1;
+        self.moves[index] = self.moves[self.len];
+        mv
+    }
+
+    /// Swaps the moves at two indices
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.as_mut_slice().swap(a, b);
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl std::ops::Index<usize> for MoveList {
+    type Output = Move;
+    fn index(&self, index: usize) -> &Move {
+        &self.as_slice()[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Gets the algebraic name of a square (0-63), e.g. "e4"
+pub fn square_name(square: u8) -> String {
+    debug_assert!(square < 64, "square must be in range 0-63");
+    let file = (b'a' + square % 8) as char;
+    let rank = (b'1' + square / 8) as char;
+    format!("{}{}", file, rank)
+}
+
+/// Parses an algebraic square name such as "e4" into a square index (0-63)
+pub fn parse_square(name: &str) -> Option<u8> {
+    let bytes = name.as_bytes();
+    if bytes.len() != 2 {
+        return None;
+    }
+    let file = bytes[0].wrapping_sub(b'a');
+    let rank = bytes[1].wrapping_sub(b'1');
+    if file < 8 && rank < 8 {
+        Some(rank * 8 + file)
+    } else {
+        None
+    }
+}
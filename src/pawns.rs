@@ -0,0 +1,144 @@
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Color, PieceType};
+use crate::constants::{FILE_A, FILE_H};
+
+/// Pawn structure features for one side, as bitboards of the pawns or squares involved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PawnStructure {
+    pub passed: Bitboard,
+    pub isolated: Bitboard,
+    pub doubled: Bitboard,
+    pub backward: Bitboard,
+    pub connected: Bitboard,
+    /// Pawns grouped by runs of adjacent files, from the a-file side
+    pub islands: Vec<Bitboard>,
+    /// Files without pawns of this side but with enemy pawns, as full files
+    pub half_open_files: Bitboard,
+    pub front_spans: Bitboard,
+    pub attack_spans: Bitboard,
+}
+
+impl Board {
+    /// Analyzes the pawn structure of the given color
+    pub fn pawn_structure(&self, color: Color) -> PawnStructure {
+        let ours = self.get_pieces(PieceType::Pawn, color);
+        let theirs = self.get_pieces(PieceType::Pawn, color.opposite());
+        PawnStructure {
+            passed: passed_pawns(color, ours, theirs),
+            isolated: isolated_pawns(ours),
+            doubled: doubled_pawns(ours),
+            backward: backward_pawns(color, ours, theirs),
+            connected: connected_pawns(color, ours),
+            islands: pawn_islands(ours),
+            half_open_files: half_open_files(ours, theirs),
+            front_spans: front_spans(color, ours),
+            attack_spans: attack_spans(color, ours),
+        }
+    }
+
+    /// Gets the files without pawns of either color, as full files
+    pub fn open_files(&self) -> Bitboard {
+        let pawns = self.get_pieces(PieceType::Pawn, Color::White)
+            | self.get_pieces(PieceType::Pawn, Color::Black);
+        open_files(pawns)
+    }
+}
+
+/// Gets the squares in front of the pawns on their files, towards promotion
+pub fn front_spans(color: Color, pawns: Bitboard) -> Bitboard {
+    pawns.front_span(color)
+}
+
+/// Gets the squares behind the pawns on their files
+pub fn rear_spans(color: Color, pawns: Bitboard) -> Bitboard {
+    pawns.rear_span(color)
+}
+
+/// Gets the squares the pawns attack now or could attack by advancing
+pub fn attack_spans(color: Color, pawns: Bitboard) -> Bitboard {
+    let spans = front_spans(color, pawns).as_u64();
+    Bitboard::from_u64(((spans << 1) & !FILE_A) | ((spans >> 1) & !FILE_H))
+}
+
+/// Gets the squares the pawns attack
+pub fn pawn_attacks(color: Color, pawns: Bitboard) -> Bitboard {
+    let pawns = pawns.as_u64();
+    let (east, west) = match color {
+        Color::White => (pawns << 9, pawns << 7),
+        Color::Black => (pawns >> 7, pawns >> 9),
+    };
+    Bitboard::from_u64((east & !FILE_A) | (west & !FILE_H))
+}
+
+/// Gets the pawns with no enemy pawn in front of them on their own or adjacent files
+pub fn passed_pawns(color: Color, ours: Bitboard, theirs: Bitboard) -> Bitboard {
+    let them = color.opposite();
+    ours & !(front_spans(them, theirs) | attack_spans(them, theirs))
+}
+
+/// Gets the pawns with no friendly pawns on adjacent files
+pub fn isolated_pawns(pawns: Bitboard) -> Bitboard {
+    let files = pawns.file_fill().as_u64();
+    let neighbours = ((files << 1) & !FILE_A) | ((files >> 1) & !FILE_H);
+    pawns & !Bitboard::from_u64(neighbours)
+}
+
+/// Gets the pawns sharing their file with another friendly pawn
+pub fn doubled_pawns(pawns: Bitboard) -> Bitboard {
+    pawns & (pawns.front_span(Color::White) | pawns.rear_span(Color::White))
+}
+
+/// Gets the pawns whose stop square is attacked by an enemy pawn and can no longer be
+/// guarded by a friendly pawn, since none are level with or behind them on adjacent
+/// files
+pub fn backward_pawns(color: Color, ours: Bitboard, theirs: Bitboard) -> Bitboard {
+    let stops = advance(color, ours.as_u64());
+    let exposed = Bitboard::from_u64(stops)
+        & pawn_attacks(color.opposite(), theirs)
+        & !attack_spans(color, ours);
+    Bitboard::from_u64(advance(color.opposite(), exposed.as_u64()))
+}
+
+/// Gets the pawns defended by a friendly pawn or standing next to one on the same rank
+pub fn connected_pawns(color: Color, pawns: Bitboard) -> Bitboard {
+    let bits = pawns.as_u64();
+    let phalanx = ((bits << 1) & !FILE_A) | ((bits >> 1) & !FILE_H);
+    pawns & (pawn_attacks(color, pawns) | Bitboard::from_u64(phalanx))
+}
+
+/// Splits the pawns into islands separated by files without pawns
+pub fn pawn_islands(pawns: Bitboard) -> Vec<Bitboard> {
+    let mut islands = Vec::new();
+    let mut island = 0u64;
+    for file in 0..8 {
+        let on_file = pawns.as_u64() & (FILE_A << file);
+        if on_file != 0 {
+            island |= on_file;
+        } else if island != 0 {
+            islands.push(Bitboard::from_u64(island));
+            island = 0;
+        }
+    }
+    if island != 0 {
+        islands.push(Bitboard::from_u64(island));
+    }
+    islands
+}
+
+/// Gets the files without any of the given pawns, as full files
+pub fn open_files(pawns: Bitboard) -> Bitboard {
+    !pawns.file_fill()
+}
+
+/// Gets the files without our pawns but with enemy pawns, as full files
+pub fn half_open_files(ours: Bitboard, theirs: Bitboard) -> Bitboard {
+    !ours.file_fill() & theirs.file_fill()
+}
+
+// Moves every bit one rank towards the color's promotion rank
+fn advance(color: Color, bits: u64) -> u64 {
+    match color {
+        Color::White => bits << 8,
+        Color::Black => bits >> 8,
+    }
+}
@@ -0,0 +1,554 @@
+use crate::board::Board;
+use crate::movepick::{History, MovePicker};
+use crate::moves::{Move, MoveList};
+use crate::timeman::{Clock, TimeManager};
+use crate::tt::{Bound, TranspositionTable};
+use crate::variant::Outcome;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub use crate::constants::{MATE, MAX_PLY};
+
+/// Bound above every reachable score
+pub const INFINITY: i32 = MATE + 1;
+
+// How many nodes pass between checks of the clock and the stop flag
+const CHECK_INTERVAL: u64 = 2048;
+
+/// Gets the score of giving mate in the given number of plies
+pub fn mate_in(ply: usize) -> i32 {
+    MATE - ply as i32
+}
+
+/// Gets the score of being mated in the given number of plies
+pub fn mated_in(ply: usize) -> i32 {
+    -MATE + ply as i32
+}
+
+/// Returns true if the score is a forced mate for either side
+pub fn is_mate_score(score: i32) -> bool {
+    score.abs() >= MATE - MAX_PLY as i32
+}
+
+/// Gets the number of moves (not plies) until mate for a mate score, negative when the
+/// side to move is getting mated
+pub fn mate_distance(score: i32) -> Option<i32> {
+    if !is_mate_score(score) {
+        return None;
+    }
+    let plies = MATE - score.abs();
+    Some(if score > 0 {
+        (plies + 1) / 2
+    } else {
+        -plies / 2
+    })
+}
+
+/// When a search should stop. Unset limits are unbounded, and iterative deepening
+/// always finishes depth 1 so there is a move to play.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    /// Exact time to search for
+    pub time: Option<Duration>,
+    /// Game clock to budget time from with a `TimeManager`, if no exact time is set
+    pub clock: Option<Clock>,
+}
+
+impl SearchLimits {
+    /// Searches to a fixed depth in plies
+    pub fn depth(depth: u32) -> Self {
+        SearchLimits {
+            depth: Some(depth),
+            ..Default::default()
+        }
+    }
+
+    /// Searches until about the given number of nodes have been visited
+    pub fn nodes(nodes: u64) -> Self {
+        SearchLimits {
+            nodes: Some(nodes),
+            ..Default::default()
+        }
+    }
+
+    /// Searches for the given amount of time
+    pub fn time(time: Duration) -> Self {
+        SearchLimits {
+            time: Some(time),
+            ..Default::default()
+        }
+    }
+
+    /// Searches for as long as the time manager allots from the clock
+    pub fn clock(clock: Clock) -> Self {
+        SearchLimits {
+            clock: Some(clock),
+            ..Default::default()
+        }
+    }
+
+    // Creates the time manager for a search starting now, if time is limited
+    fn time_manager(&self) -> Option<TimeManager> {
+        match (self.time, self.clock) {
+            (Some(time), _) => Some(TimeManager::fixed(time)),
+            (None, Some(clock)) => Some(TimeManager::new(clock)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// The outcome of the last completed iteration of a search
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchResult {
+    /// Best move found, or None when the side to move has no legal moves
+    pub best_move: Option<Move>,
+    /// Score in centipawns from the side to move's perspective, or a mate score
+    pub score: i32,
+    pub depth: u32,
+    /// Deepest ply reached, including quiescence
+    pub seldepth: usize,
+    pub nodes: u64,
+    pub time: Duration,
+    /// Principal variation, starting with the best move
+    pub pv: Vec<Move>,
+    /// Rank of the line when several are searched, 1 for the best
+    pub multipv: usize,
+}
+
+/// Iterative deepening negamax alpha-beta search with quiescence and a transposition
+/// table. With more than one thread the search is a Lazy SMP search: helper threads
+/// search the same position at staggered depths, each with its own history, and speed
+/// up the main thread through the shared table.
+#[derive(Debug)]
+pub struct Searcher {
+    limits: SearchLimits,
+    stop: Arc<AtomicBool>,
+    tt: Arc<TranspositionTable>,
+    history: History,
+    // Moves made from the root to the current node
+    line: Vec<Move>,
+    multi_pv: usize,
+    threads: usize,
+    // Root moves already reported as better lines in the current iteration
+    excluded: Vec<Move>,
+    time: Option<TimeManager>,
+    start: Instant,
+    nodes: u64,
+    seldepth: usize,
+    stopped: bool,
+}
+
+impl Searcher {
+    /// Creates a searcher that stops at the given limits, with a table of its own
+    pub fn new(limits: SearchLimits) -> Self {
+        Self::with_table(limits, Arc::new(TranspositionTable::default()))
+    }
+
+    /// Creates a searcher using a shared transposition table, which keeps its entries
+    /// between searches
+    pub fn with_table(limits: SearchLimits, tt: Arc<TranspositionTable>) -> Self {
+        Searcher {
+            limits,
+            stop: Arc::new(AtomicBool::new(false)),
+            tt,
+            history: History::new(),
+            line: Vec::new(),
+            multi_pv: 1,
+            threads: 1,
+            excluded: Vec::new(),
+            time: None,
+            start: Instant::now(),
+            nodes: 0,
+            seldepth: 0,
+            stopped: false,
+        }
+    }
+
+    /// Gets the transposition table
+    pub fn table(&self) -> &Arc<TranspositionTable> {
+        &self.tt
+    }
+
+    /// Sets how many of the best root moves get their own principal variation
+    pub fn set_multi_pv(&mut self, lines: usize) {
+        self.multi_pv = lines.max(1);
+    }
+
+    /// Sets how many threads search, 1 by default. A single thread searches
+    /// deterministically: the same position, limits and table contents always give the
+    /// same result, which is not the case with helper threads.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Gets a flag that stops the search as soon as it is set, e.g. from another thread
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    /// Searches the position and returns the result of the deepest completed iteration.
+    /// The board is restored before returning.
+    pub fn search(&mut self, board: &mut Board) -> SearchResult {
+        self.search_with(board, |_| {})
+    }
+
+    /// Searches like `search`, calling `on_iteration` after every completed depth, once
+    /// for each line when several are searched
+    pub fn search_with<F: FnMut(&SearchResult)>(
+        &mut self,
+        board: &mut Board,
+        mut on_iteration: F,
+    ) -> SearchResult {
+        self.start = Instant::now();
+        self.time = self.limits.time_manager();
+        self.nodes = 0;
+        self.seldepth = 0;
+        self.stopped = false;
+        self.tt.new_search();
+        self.history.clear_killers();
+        self.line.clear();
+
+        let mut moves = MoveList::new();
+        board.generate_moves(&mut moves);
+        let mut result = SearchResult {
+            best_move: moves.iter().next().copied(),
+            multipv: 1,
+            ..Default::default()
+        };
+        if moves.is_empty() {
+            result.score = self.terminal_score(board, 0);
+            return result;
+        }
+
+        if let Some(time) = &mut self.time {
+            time.set_legal_moves(moves.len());
+        }
+
+        // Helpers run until the main thread is done with the search
+        let helpers_stop = Arc::new(AtomicBool::new(false));
+        let (mut result, helper_nodes) = thread::scope(|scope| {
+            let helpers: Vec<_> = (1..self.threads)
+                .map(|index| {
+                    let mut helper = self.helper(Arc::clone(&helpers_stop));
+                    let mut board = board.clone();
+                    // Half the helpers start a depth ahead so the threads spread out
+                    let start_depth = 1 + index as u32 % 2;
+                    scope.spawn(move || helper.deepen(&mut board, start_depth))
+                })
+                .collect();
+            let result = self.iterate(board, moves.len(), result, &mut on_iteration);
+            helpers_stop.store(true, Ordering::Relaxed);
+            let helper_nodes: u64 = helpers
+                .into_iter()
+                .map(|helper| helper.join().expect("search thread panicked"))
+                .sum();
+            (result, helper_nodes)
+        });
+        result.nodes = self.nodes + helper_nodes;
+        result.time = self.start.elapsed();
+        result
+    }
+
+    // Runs the main thread's iterative deepening, reporting every completed line
+    fn iterate<F: FnMut(&SearchResult)>(
+        &mut self,
+        board: &mut Board,
+        legal_moves: usize,
+        mut result: SearchResult,
+        on_iteration: &mut F,
+    ) -> SearchResult {
+        let lines = self.multi_pv.min(legal_moves);
+        let max_depth = self.limits.depth.unwrap_or(MAX_PLY as u32 - 1).max(1);
+        'deepening: for depth in 1..=max_depth.min(MAX_PLY as u32 - 1) {
+            self.excluded.clear();
+            for multipv in 1..=lines {
+                let mut pv = Vec::new();
+                let score = self.negamax(board, depth, 0, -INFINITY, INFINITY, &mut pv);
+                if self.stopped && (depth > 1 || multipv > 1) {
+                    break 'deepening;
+                }
+
+                let line = SearchResult {
+                    best_move: pv.first().copied().or(result.best_move),
+                    score,
+                    depth,
+                    seldepth: self.seldepth,
+                    nodes: self.nodes,
+                    time: self.start.elapsed(),
+                    pv,
+                    multipv,
+                };
+                on_iteration(&line);
+                if let Some(mv) = line.best_move {
+                    self.excluded.push(mv);
+                }
+                if multipv == 1 {
+                    result = line;
+                }
+                if self.stopped {
+                    break 'deepening;
+                }
+            }
+
+            if is_mate_score(result.score) && self.limits.depth.is_none() {
+                break;
+            }
+            if let Some(time) = &mut self.time {
+                time.update(&result);
+                if time.should_stop() {
+                    break;
+                }
+            }
+        }
+        result
+    }
+
+    // Creates a helper thread's searcher, sharing the table but with a history of its
+    // own, which stops when `stop` is set
+    fn helper(&self, stop: Arc<AtomicBool>) -> Searcher {
+        let limits = SearchLimits {
+            depth: self.limits.depth,
+            ..Default::default()
+        };
+        Searcher {
+            stop,
+            ..Searcher::with_table(limits, Arc::clone(&self.tt))
+        }
+    }
+
+    // Deepens a helper's search from `start_depth` until stopped or out of depths,
+    // returning the nodes searched
+    fn deepen(&mut self, board: &mut Board, start_depth: u32) -> u64 {
+        let max_depth = self.limits.depth.unwrap_or(MAX_PLY as u32 - 1);
+        for depth in start_depth..=max_depth.min(MAX_PLY as u32 - 1) {
+            let mut pv = Vec::new();
+            self.negamax(board, depth, 0, -INFINITY, INFINITY, &mut pv);
+            if self.stopped {
+                break;
+            }
+        }
+        self.nodes
+    }
+
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        mut beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        pv.clear();
+        if depth == 0 || ply >= MAX_PLY - 1 {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+
+        self.visit(ply);
+        if self.stopped {
+            return 0;
+        }
+
+        if ply > 0 {
+            if board.halfmove_clock() >= 100
+                || board.is_repetition()
+                || board.variant().is_insufficient_material(board)
+            {
+                return 0;
+            }
+            // No line from here can beat a mate found closer to the root
+            alpha = alpha.max(mated_in(ply));
+            beta = beta.min(mate_in(ply + 1));
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+
+        let hash = board.hash();
+        let entry = self.tt.probe(hash, ply);
+        if let Some(entry) = entry.filter(|entry| ply > 0 && entry.depth as u32 >= depth) {
+            let cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => entry.score >= beta,
+                Bound::Upper => entry.score <= alpha,
+            };
+            if cutoff {
+                return entry.score;
+            }
+        }
+
+        let hash_move = entry.and_then(|entry| entry.best_move);
+        let previous = self.line.last().copied();
+        let mut picker = MovePicker::new(board, hash_move, &self.history, ply, previous);
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        let mut best_move = None;
+        let mut quiets = MoveList::new();
+        let mut child_pv = Vec::new();
+        while let Some(mv) = picker.next(board, &self.history) {
+            if ply == 0 && self.excluded.contains(&mv) {
+                continue;
+            }
+            board.make_move(mv);
+            self.line.push(mv);
+            let score = -self.negamax(board, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            self.line.pop();
+            board.unmake_move(mv);
+            if self.stopped {
+                return 0;
+            }
+            let quiet = !mv.is_capture() && !mv.is_promotion();
+            if quiet {
+                quiets.push(mv);
+            }
+
+            if score > best {
+                best = score;
+                best_move = Some(mv);
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(mv);
+                    pv.extend_from_slice(&child_pv);
+                    if alpha >= beta {
+                        if quiet {
+                            let color = board.side_to_move();
+                            self.history.update_quiet_cutoff(
+                                color,
+                                ply,
+                                previous,
+                                mv,
+                                depth,
+                                quiets.as_slice(),
+                            );
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        if best_move.is_none() {
+            return self.terminal_score(board, ply);
+        }
+        if ply == 0 && !self.excluded.is_empty() {
+            return best;
+        }
+
+        let bound = if best >= beta {
+            Bound::Lower
+        } else if best > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        self.tt
+            .store(hash, depth as u8, bound, best, best_move, ply);
+        best
+    }
+
+    // Searches captures and promotions until the position is quiet, so the static
+    // evaluation is never taken in the middle of an exchange. Every evasion is searched
+    // when in check.
+    fn quiescence(&mut self, board: &mut Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.visit(ply);
+        if self.stopped {
+            return 0;
+        }
+
+        let in_check = board.is_in_check();
+        // In variants the game can end without a mate, which shows only as a position
+        // without moves; a mate is found by running out of evasions below
+        if !in_check && !board.variant().has_standard_moves() {
+            let mut moves = MoveList::new();
+            board.generate_moves(&mut moves);
+            if moves.is_empty() {
+                return self.terminal_score(board, ply);
+            }
+        }
+        if ply >= MAX_PLY - 1 {
+            return board.evaluate();
+        }
+
+        let mut best = -INFINITY;
+        if !in_check {
+            // Standing pat: the side to move can usually decline every capture, but not
+            // a check
+            let stand_pat = board.evaluate();
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+            best = stand_pat;
+        }
+
+        let hash_move = self
+            .tt
+            .probe(board.hash(), ply)
+            .and_then(|entry| entry.best_move);
+        let mut picker = if in_check {
+            let previous = self.line.last().copied();
+            MovePicker::new(board, hash_move, &self.history, ply, previous)
+        } else {
+            MovePicker::captures(board, hash_move)
+        };
+        while let Some(mv) = picker.next(board, &self.history) {
+            board.make_move(mv);
+            self.line.push(mv);
+            let score = -self.quiescence(board, ply + 1, -beta, -alpha);
+            self.line.pop();
+            board.unmake_move(mv);
+            if self.stopped {
+                return 0;
+            }
+
+            if score > best {
+                best = score;
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+        if best == -INFINITY {
+            return self.terminal_score(board, ply);
+        }
+        best
+    }
+
+    // Counts a node and checks the limits every so often
+    fn visit(&mut self, ply: usize) {
+        self.nodes += 1;
+        self.seldepth = self.seldepth.max(ply);
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes) {
+            self.stopped = true;
+        }
+        if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            let out_of_time = self.time.as_ref().is_some_and(TimeManager::out_of_time);
+            if out_of_time || self.stop.load(Ordering::Relaxed) {
+                self.stopped = true;
+            }
+        }
+    }
+
+    // Scores a position without legal moves, following the board's variant
+    fn terminal_score(&self, board: &Board, ply: usize) -> i32 {
+        match board.outcome() {
+            Some(Outcome::Win(color)) if color == board.side_to_move() => mate_in(ply),
+            Some(Outcome::Win(_)) => mated_in(ply),
+            _ => 0,
+        }
+    }
+}
+
+impl Board {
+    /// Searches the position with a fresh `Searcher` and returns the best move and score
+    pub fn search(&mut self, limits: SearchLimits) -> SearchResult {
+        Searcher::new(limits).search(self)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::attacks::*;
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Color, PieceType, PIECE_TYPES};
+use crate::moves::{Move, MoveKind};
+
+/// Gets the material value of a piece used by static exchange evaluation
+pub fn see_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 100,
+        PieceType::Knight => 300,
+        PieceType::Bishop => 300,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 20000,
+    }
+}
+
+// The state of a capture sequence on one square
+struct Exchange {
+    to: u8,
+    occupied: Bitboard,
+    attackers: Bitboard,
+    // Indexed by the color of the pinned pieces
+    pinned: [Bitboard; 2],
+    pinners: [Bitboard; 2],
+}
+
+impl Board {
+    /// Statically evaluates the material balance of the full capture sequence started by
+    /// the move, assuming both sides recapture with their least valuable piece and may
+    /// stop whenever continuing would lose material
+    pub fn see(&self, mv: Move) -> i32 {
+        if mv.is_castle() {
+            return 0;
+        }
+
+        let mut exchange = self.start_exchange(mv);
+        let mut gain = [0i32; 32];
+        gain[0] = self.captured_value(mv);
+        let mut attacker_value = self.moved_value(mv);
+        let mut from = mv.from();
+        let mut side = self.side_to_move();
+        let mut depth = 0;
+
+        loop {
+            depth += 1;
+            gain[depth] = attacker_value - gain[depth - 1];
+            if depth == gain.len() - 1 {
+                break;
+            }
+
+            exchange.remove(self, from);
+            side = side.opposite();
+            match self.least_valuable_attacker(&exchange, side) {
+                Some((square, PieceType::King)) => {
+                    // The king may only recapture if nothing defends the square
+                    if !(exchange.attackers & self.get_color_pieces(side.opposite())).is_empty() {
+                        break;
+                    }
+                    from = square;
+                    attacker_value = see_value(PieceType::King);
+                }
+                Some((square, piece_type)) => {
+                    from = square;
+                    attacker_value = see_value(piece_type);
+                }
+                None => break,
+            }
+        }
+
+        while depth > 1 {
+            depth -= 1;
+            gain[depth - 1] = -(-gain[depth - 1]).max(gain[depth]);
+        }
+        gain[0]
+    }
+
+    /// Returns true if the static exchange evaluation of the move is at least `threshold`,
+    /// stopping as soon as the outcome is decided
+    pub fn see_ge(&self, mv: Move, threshold: i32) -> bool {
+        if mv.is_castle() {
+            return threshold <= 0;
+        }
+
+        let mut swap = self.captured_value(mv) - threshold;
+        if swap < 0 {
+            return false;
+        }
+        swap = self.moved_value(mv) - swap;
+        if swap <= 0 {
+            return true;
+        }
+
+        let mut exchange = self.start_exchange(mv);
+        exchange.remove(self, mv.from());
+        let mut side = self.side_to_move();
+        let mut result = true;
+
+        loop {
+            side = side.opposite();
+            let (square, piece_type) = match self.least_valuable_attacker(&exchange, side) {
+                Some(attacker) => attacker,
+                None => break,
+            };
+
+            if piece_type == PieceType::King {
+                // Capturing with the king only works if the square is undefended
+                let defended =
+                    !(exchange.attackers & self.get_color_pieces(side.opposite())).is_empty();
+                return if defended { result } else { !result };
+            }
+
+            result = !result;
+            swap = see_value(piece_type) - swap;
+            if swap < result as i32 {
+                break;
+            }
+            exchange.remove(self, square);
+        }
+
+        result
+    }
+
+    fn start_exchange(&self, mv: Move) -> Exchange {
+        let to = mv.to();
+        let mut occupied = self.get_all_pieces();
+        if mv.kind() == MoveKind::EnPassant {
+            let pawn_square = match self.side_to_move() {
+                Color::White => to - 8,
+                Color::Black => to + 8,
+            };
+            occupied.clear_bit(pawn_square);
+        }
+
+        Exchange {
+            to,
+            occupied,
+            attackers: self.attackers_to(to, occupied) & occupied,
+            pinned: [self.pinned(Color::White), self.pinned(Color::Black)],
+            pinners: [self.pinners(Color::Black), self.pinners(Color::White)],
+        }
+    }
+
+    // Value gained by the move itself, including the promotion bonus
+    fn captured_value(&self, mv: Move) -> i32 {
+        let captured = match mv.kind() {
+            MoveKind::EnPassant => see_value(PieceType::Pawn),
+            _ => self
+                .get_piece_at(mv.to())
+                .map_or(0, |(piece_type, _)| see_value(piece_type)),
+        };
+        match mv.promotion() {
+            Some(promotion) => captured + see_value(promotion) - see_value(PieceType::Pawn),
+            None => captured,
+        }
+    }
+
+    // Value of the piece standing on the target square after the move
+    fn moved_value(&self, mv: Move) -> i32 {
+        match mv.promotion().or(mv.drop_piece()) {
+            Some(piece_type) => see_value(piece_type),
+            None => self
+                .get_piece_at(mv.from())
+                .map_or(0, |(piece_type, _)| see_value(piece_type)),
+        }
+    }
+
+    fn least_valuable_attacker(&self, exchange: &Exchange, side: Color) -> Option<(u8, PieceType)> {
+        let index = side as usize;
+        let mut attackers = exchange.attackers & self.get_color_pieces(side);
+
+        // Pinned pieces cannot join while their pinner is still on the board
+        if !(exchange.pinners[index] & exchange.occupied).is_empty() {
+            attackers = attackers & !exchange.pinned[index];
+        }
+
+        PIECE_TYPES.iter().find_map(|&piece_type| {
+            (attackers & self.get_pieces(piece_type, side))
+                .lsb()
+                .map(|square| (square, piece_type))
+        })
+    }
+}
+
+impl Exchange {
+    // Takes a piece off the square and reveals any sliders behind it
+    fn remove(&mut self, board: &Board, square: u8) {
+        self.occupied.clear_bit(square);
+        let diagonal = board.get_pieces(PieceType::Bishop, Color::White)
+            | board.get_pieces(PieceType::Bishop, Color::Black)
+            | board.get_pieces(PieceType::Queen, Color::White)
+            | board.get_pieces(PieceType::Queen, Color::Black);
+        let orthogonal = board.get_pieces(PieceType::Rook, Color::White)
+            | board.get_pieces(PieceType::Rook, Color::Black)
+            | board.get_pieces(PieceType::Queen, Color::White)
+            | board.get_pieces(PieceType::Queen, Color::Black);
+        self.attackers = (self.attackers
+            | (bishop_attacks(self.to, self.occupied) & diagonal)
+            | (rook_attacks(self.to, self.occupied) & orthogonal))
+            & self.occupied;
+    }
+}
@@ -0,0 +1,148 @@
+use crate::moves::Move;
+use crate::search::SearchResult;
+use std::time::{Duration, Instant};
+
+/// Time kept back on every move for communication and scheduling delays
+pub const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+
+/// Moves the remaining time is spread over when the time control does not say
+pub const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+// How many times the planned time a single move may use at most
+const MAX_OVERSHOOT: u32 = 5;
+
+// Score drop, in centipawns, at which the thinking time is doubled
+const MAX_SCORE_DROP: i32 = 200;
+
+// Iterations the best move must survive before it is considered clear
+const CLEAR_MOVE_ITERATIONS: u32 = 5;
+
+/// The clock of the side to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clock {
+    pub remaining: Duration,
+    pub increment: Duration,
+    /// Moves until the next time control, or None if the remaining time is for the
+    /// rest of the game
+    pub moves_to_go: Option<u32>,
+}
+
+/// Decides how long to think about a move. The remaining time is split into a soft
+/// limit, checked between iterations, and a hard limit the search must never pass.
+/// The soft limit is stretched while the best move keeps changing or the score drops,
+/// and shrunk for forced moves and best moves that stay put. The clock starts when the
+/// manager is created.
+#[derive(Debug, Clone)]
+pub struct TimeManager {
+    start: Instant,
+    soft: Duration,
+    hard: Duration,
+    // False for a fixed time per move, which is used exactly
+    adjustable: bool,
+    forced: bool,
+    best_move: Option<Move>,
+    // Recent best move changes, halved every iteration
+    instability: f64,
+    stable_iterations: u32,
+    previous_score: Option<i32>,
+    score_drop: i32,
+}
+
+impl TimeManager {
+    /// Plans the time for a move from the clock: an even share of the remaining time
+    /// over the moves to go plus most of the increment, which may be overshot up to a
+    /// fixed multiple but never into the last fifth of the clock
+    pub fn new(clock: Clock) -> Self {
+        let available = clock.remaining.saturating_sub(MOVE_OVERHEAD);
+        let moves = clock.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+        let planned = available / moves + clock.increment * 3 / 4;
+        let hard = (planned * MAX_OVERSHOOT).min(available * 4 / 5);
+        TimeManager {
+            soft: planned.min(hard),
+            hard,
+            adjustable: true,
+            ..Self::fixed(Duration::ZERO)
+        }
+    }
+
+    /// Uses exactly the given time for the move
+    pub fn fixed(time: Duration) -> Self {
+        TimeManager {
+            start: Instant::now(),
+            soft: time,
+            hard: time,
+            adjustable: false,
+            forced: false,
+            best_move: None,
+            instability: 0.0,
+            stable_iterations: 0,
+            previous_score: None,
+            score_drop: 0,
+        }
+    }
+
+    /// Tells the manager how many legal moves there are, so a forced move is played
+    /// as soon as one iteration is done
+    pub fn set_legal_moves(&mut self, count: usize) {
+        self.forced = self.adjustable && count <= 1;
+    }
+
+    /// Feeds the manager the result of a completed iteration
+    pub fn update(&mut self, result: &SearchResult) {
+        self.instability /= 2.0;
+        if result.best_move == self.best_move {
+            self.stable_iterations += 1;
+        } else {
+            if self.best_move.is_some() {
+                self.instability += 1.0;
+            }
+            self.best_move = result.best_move;
+            self.stable_iterations = 0;
+        }
+        if let Some(previous) = self.previous_score {
+            self.score_drop = (previous - result.score).clamp(0, MAX_SCORE_DROP);
+        }
+        self.previous_score = Some(result.score);
+    }
+
+    /// Gets the time after which no new iteration should start
+    pub fn soft_limit(&self) -> Duration {
+        if self.forced {
+            return Duration::ZERO;
+        }
+        if !self.adjustable {
+            return self.soft;
+        }
+        let instability = 1.0 + self.instability.min(1.5);
+        let falling = 1.0 + self.score_drop as f64 / MAX_SCORE_DROP as f64;
+        let clear = if self.stable_iterations >= CLEAR_MOVE_ITERATIONS {
+            0.5
+        } else {
+            1.0
+        };
+        self.soft
+            .mul_f64(instability * falling * clear)
+            .min(self.hard)
+    }
+
+    /// Gets the time at which the search must stop
+    pub fn hard_limit(&self) -> Duration {
+        self.hard
+    }
+
+    /// Gets the time since the manager was created
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns true if the soft limit has passed, so the last completed iteration
+    /// should be played
+    pub fn should_stop(&self) -> bool {
+        self.elapsed() >= self.soft_limit()
+    }
+
+    /// Returns true if the hard limit has passed
+    pub fn out_of_time(&self) -> bool {
+        self.elapsed() >= self.hard
+    }
+}
@@ -0,0 +1,264 @@
+use crate::constants::{MATE, MAX_PLY};
+use crate::moves::Move;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+/// Size of a table created without a size, in megabytes
+pub const DEFAULT_HASH_MB: usize = 16;
+
+// Entries sharing an index; a bucket fills one 64-byte cache line
+const BUCKET_SIZE: usize = 4;
+
+/// How a stored score relates to the true score of the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is exact
+    Exact,
+    /// The search failed high, so the true score is at least the stored one
+    Lower,
+    /// The search failed low, so the true score is at most the stored one
+    Upper,
+}
+
+/// A decoded table entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TtEntry {
+    pub best_move: Option<Move>,
+    /// Score adjusted back to the ply it was probed from
+    pub score: i32,
+    pub depth: u8,
+    pub bound: Bound,
+    /// Search generation the entry was written in
+    pub age: u8,
+}
+
+// A key and packed entry. The key is stored xored with the data, so a slot torn by a
+// concurrent write fails the key check instead of returning mixed-up data.
+#[derive(Debug, Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+#[repr(align(64))]
+struct Bucket([Slot; BUCKET_SIZE]);
+
+/// A transposition table keyed by Zobrist hash. All probes and stores take `&self` and
+/// use relaxed atomics, so one table can be shared between searching threads.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    buckets: Vec<Bucket>,
+    age: AtomicU8,
+}
+
+impl TranspositionTable {
+    /// Creates a table using about the given number of megabytes
+    pub fn new(megabytes: usize) -> Self {
+        let mut table = TranspositionTable {
+            buckets: Vec::new(),
+            age: AtomicU8::new(0),
+        };
+        table.resize(megabytes);
+        table
+    }
+
+    /// Reallocates the table to about the given number of megabytes, clearing it
+    pub fn resize(&mut self, megabytes: usize) {
+        let count = (megabytes * 1024 * 1024 / std::mem::size_of::<Bucket>()).max(1);
+        self.buckets = (0..count).map(|_| Bucket::default()).collect();
+        self.age.store(0, Ordering::Relaxed);
+    }
+
+    /// Removes every entry
+    pub fn clear(&self) {
+        for bucket in &self.buckets {
+            for slot in &bucket.0 {
+                slot.key.store(0, Ordering::Relaxed);
+                slot.data.store(0, Ordering::Relaxed);
+            }
+        }
+        self.age.store(0, Ordering::Relaxed);
+    }
+
+    /// Gets the number of entries the table can hold
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * BUCKET_SIZE
+    }
+
+    /// Starts a new search generation, so entries from earlier searches are replaced first
+    pub fn new_search(&self) {
+        self.age.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Gets the current search generation
+    pub fn age(&self) -> u8 {
+        self.age.load(Ordering::Relaxed)
+    }
+
+    /// Looks up a position, adjusting mate scores to be relative to `ply`
+    pub fn probe(&self, hash: u64, ply: usize) -> Option<TtEntry> {
+        self.bucket(hash).0.iter().find_map(|slot| {
+            let data = slot.data.load(Ordering::Relaxed);
+            let key = slot.key.load(Ordering::Relaxed);
+            if data == 0 || key ^ data != hash {
+                return None;
+            }
+            let mut entry = unpack(data)?;
+            entry.score = score_from_tt(entry.score, ply);
+            Some(entry)
+        })
+    }
+
+    /// Stores a search result for a position found at `ply`. An existing entry for the
+    /// position keeps its move if none is given and is only overwritten by exact or
+    /// similarly deep results; otherwise the shallowest, oldest entry in the bucket
+    /// is replaced.
+    pub fn store(
+        &self,
+        hash: u64,
+        depth: u8,
+        bound: Bound,
+        score: i32,
+        best_move: Option<Move>,
+        ply: usize,
+    ) {
+        let age = self.age();
+        let bucket = self.bucket(hash);
+        let mut target = &bucket.0[0];
+        let mut target_worth = i32::MAX;
+        let mut best_move = best_move;
+
+        for slot in &bucket.0 {
+            let data = slot.data.load(Ordering::Relaxed);
+            let key = slot.key.load(Ordering::Relaxed);
+            let old = if data == 0 { None } else { unpack(data) };
+            let old = match old {
+                Some(old) => old,
+                None => {
+                    if target_worth > i32::MIN {
+                        target = slot;
+                        target_worth = i32::MIN;
+                    }
+                    continue;
+                }
+            };
+
+            if key ^ data == hash {
+                if bound != Bound::Exact && depth + 4 < old.depth && old.age == age {
+                    return;
+                }
+                best_move = best_move.or(old.best_move);
+                target = slot;
+                break;
+            }
+
+            let relative_age = age.wrapping_sub(old.age) as i32;
+            let worth = old.depth as i32 - 8 * relative_age;
+            if worth < target_worth {
+                target = slot;
+                target_worth = worth;
+            }
+        }
+
+        let entry = TtEntry {
+            best_move,
+            score: score_to_tt(score, ply),
+            depth,
+            bound,
+            age,
+        };
+        let data = pack(&entry);
+        target.data.store(data, Ordering::Relaxed);
+        target.key.store(hash ^ data, Ordering::Relaxed);
+    }
+
+    /// Gets how full the table is in permille, sampled from entries of the current
+    /// search generation in the first thousand slots
+    pub fn hashfull(&self) -> usize {
+        let age = self.age();
+        let slots = self.buckets.iter().flat_map(|bucket| bucket.0.iter());
+        let sampled = slots.take(1000);
+        let mut total = 0;
+        let mut used = 0;
+        for slot in sampled {
+            total += 1;
+            let data = slot.data.load(Ordering::Relaxed);
+            if data != 0 && unpack(data).is_some_and(|entry| entry.age == age) {
+                used += 1;
+            }
+        }
+        used * 1000 / total.max(1)
+    }
+
+    fn bucket(&self, hash: u64) -> &Bucket {
+        let index = ((hash as u128 * self.buckets.len() as u128) >> 64) as usize;
+        &self.buckets[index]
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_HASH_MB)
+    }
+}
+
+/// Converts a score relative to the root into one relative to the position at `ply`,
+/// so a stored mate is found at the right distance from wherever it is probed
+pub fn score_to_tt(score: i32, ply: usize) -> i32 {
+    if score >= MATE - MAX_PLY as i32 {
+        score + ply as i32
+    } else if score <= -MATE + MAX_PLY as i32 {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+/// Converts a stored score back into one relative to the root, the inverse of
+/// `score_to_tt`
+pub fn score_from_tt(score: i32, ply: usize) -> i32 {
+    if score >= MATE - MAX_PLY as i32 {
+        score - ply as i32
+    } else if score <= -MATE + MAX_PLY as i32 {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+// Layout: move + 1 in bits 0-17 (0 for none), score in 18-33, depth in 34-41, bound in
+// 42-43 (never 0, which marks an empty slot) and age in 44-51
+fn pack(entry: &TtEntry) -> u64 {
+    let mv = entry.best_move.map_or(0, |mv| mv.to_bits() as u64 + 1);
+    let bound = match entry.bound {
+        Bound::Exact => 1,
+        Bound::Lower => 2,
+        Bound::Upper => 3,
+    };
+    mv | (entry.score as i16 as u16 as u64) << 18
+        | (entry.depth as u64) << 34
+        | bound << 42
+        | (entry.age as u64) << 44
+}
+
+fn unpack(data: u64) -> Option<TtEntry> {
+    let mv = (data & 0x3FFFF) as u32;
+    let best_move = if mv == 0 {
+        None
+    } else {
+        Some(Move::from_bits(mv - 1)?)
+    };
+    let bound = match data >> 42 & 3 {
+        1 => Bound::Exact,
+        2 => Bound::Lower,
+        3 => Bound::Upper,
+        _ => return None,
+    };
+    Some(TtEntry {
+        best_move,
+        score: (data >> 18) as u16 as i16 as i32,
+        depth: (data >> 34) as u8,
+        bound,
+        age: (data >> 44) as u8,
+    })
+}
@@ -0,0 +1,347 @@
+use crate::board::{Board, Color};
+use crate::search::{mate_distance, SearchLimits, SearchResult, Searcher};
+use crate::timeman::Clock;
+use crate::tt::{TranspositionTable, DEFAULT_HASH_MB};
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Name reported by the `uci` command
+pub const ENGINE_NAME: &str = "checkbit";
+
+/// Author reported by the `uci` command
+pub const ENGINE_AUTHOR: &str = "the checkbit developers";
+
+// Largest transposition table the Hash option accepts, in megabytes
+const MAX_HASH_MB: usize = 65536;
+
+/// Largest number of search threads the Threads option accepts
+pub const MAX_THREADS: usize = 1024;
+
+// Largest number of lines the MultiPV option accepts
+const MAX_MULTI_PV: usize = 256;
+
+/// The arguments of a `go` command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GoCommand {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<Duration>,
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Option<Duration>,
+    pub binc: Option<Duration>,
+    pub movestogo: Option<u32>,
+    pub infinite: bool,
+}
+
+impl GoCommand {
+    /// Parses the arguments following `go`, ignoring unknown or malformed ones
+    pub fn parse(args: &[&str]) -> Self {
+        let mut go = GoCommand::default();
+        let mut i = 0;
+        while i < args.len() {
+            let value = args.get(i + 1).copied().unwrap_or("");
+            let millis = value
+                .parse::<i64>()
+                .ok()
+                .map(|ms| Duration::from_millis(ms.max(0) as u64));
+            let mut consumed = true;
+            match args[i] {
+                "depth" => go.depth = value.parse().ok(),
+                "nodes" => go.nodes = value.parse().ok(),
+                "movetime" => go.movetime = millis,
+                "wtime" => go.wtime = millis,
+                "btime" => go.btime = millis,
+                "winc" => go.winc = millis,
+                "binc" => go.binc = millis,
+                "movestogo" => go.movestogo = value.parse().ok(),
+                "infinite" => {
+                    go.infinite = true;
+                    consumed = false;
+                }
+                _ => consumed = false,
+            }
+            i += if consumed { 2 } else { 1 };
+        }
+        go
+    }
+
+    /// Gets the search limits for the given side to move. A fixed move time takes
+    /// precedence over the clock, which is left to the time manager.
+    pub fn limits(&self, side_to_move: Color) -> SearchLimits {
+        let mut limits = SearchLimits {
+            depth: self.depth,
+            nodes: self.nodes,
+            time: self.movetime,
+            clock: None,
+        };
+        if self.infinite || limits.time.is_some() {
+            return limits;
+        }
+        let (time, inc) = match side_to_move {
+            Color::White => (self.wtime, self.winc),
+            Color::Black => (self.btime, self.binc),
+        };
+        limits.clock = time.map(|remaining| Clock {
+            remaining,
+            increment: inc.unwrap_or_default(),
+            moves_to_go: self.movestogo,
+        });
+        limits
+    }
+}
+
+/// Formats a completed iteration of a search from the given position as a UCI `info`
+/// line
+pub fn info_line(board: &Board, result: &SearchResult, hashfull: usize) -> String {
+    let score = match mate_distance(result.score) {
+        Some(moves) => format!("mate {}", moves),
+        None => format!("cp {}", result.score),
+    };
+    let millis = result.time.as_millis() as u64;
+    let nps = result.nodes * 1000 / millis.max(1);
+    let mut line = format!(
+        "info depth {} seldepth {} multipv {} score {} nodes {} nps {} hashfull {} time {}",
+        result.depth, result.seldepth, result.multipv, score, result.nodes, nps, hashfull, millis
+    );
+    if !result.pv.is_empty() {
+        line.push_str(" pv");
+        for mv in &result.pv {
+            line.push_str(&format!(" {}", board.move_name(*mv)));
+        }
+    }
+    line
+}
+
+// A search running on its own thread
+struct RunningSearch {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+    infinite: bool,
+}
+
+/// A UCI engine session. Commands are handled one line at a time; searches run on a
+/// background thread so `stop`, `isready` and `quit` are answered while thinking.
+pub struct Uci<W: Write + Send + 'static> {
+    output: Arc<Mutex<W>>,
+    board: Board,
+    tt: Arc<TranspositionTable>,
+    hash_mb: usize,
+    multi_pv: usize,
+    threads: usize,
+    chess960: bool,
+    search: Option<RunningSearch>,
+}
+
+impl<W: Write + Send + 'static> Uci<W> {
+    /// Creates a session writing its responses to `output`
+    pub fn new(output: W) -> Self {
+        Uci {
+            output: Arc::new(Mutex::new(output)),
+            board: Board::initial(),
+            tt: Arc::new(TranspositionTable::new(DEFAULT_HASH_MB)),
+            hash_mb: DEFAULT_HASH_MB,
+            multi_pv: 1,
+            threads: 1,
+            chess960: false,
+            search: None,
+        }
+    }
+
+    /// Handles one command line, returning false once the session should end
+    pub fn handle(&mut self, line: &str) -> bool {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first().copied() {
+            Some("uci") => self.uci(),
+            Some("isready") => self.send("readyok"),
+            Some("ucinewgame") => {
+                self.stop();
+                self.tt.clear();
+                self.board = Board::initial();
+            }
+            Some("position") => {
+                self.stop();
+                if let Err(message) = self.position(&tokens[1..]) {
+                    self.send(&format!("info string {}", message));
+                }
+            }
+            Some("setoption") => {
+                self.stop();
+                self.set_option(&tokens[1..]);
+            }
+            Some("go") => self.go(GoCommand::parse(&tokens[1..])),
+            Some("stop") => self.stop(),
+            Some("quit") => {
+                self.stop();
+                return false;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn uci(&self) {
+        self.send(&format!("id name {}", ENGINE_NAME));
+        self.send(&format!("id author {}", ENGINE_AUTHOR));
+        self.send(&format!(
+            "option name Hash type spin default {} min 1 max {}",
+            DEFAULT_HASH_MB, MAX_HASH_MB
+        ));
+        self.send(&format!(
+            "option name Threads type spin default 1 min 1 max {}",
+            MAX_THREADS
+        ));
+        self.send(&format!(
+            "option name MultiPV type spin default 1 min 1 max {}",
+            MAX_MULTI_PV
+        ));
+        self.send("option name UCI_Chess960 type check default false");
+        self.send("uciok");
+    }
+
+    // Handles `position startpos|fen <fen> [moves <move>...]`
+    fn position(&mut self, args: &[&str]) -> Result<(), String> {
+        let moves_at = args.iter().position(|&arg| arg == "moves");
+        let (setup, moves) = match moves_at {
+            Some(index) => (&args[..index], &args[index + 1..]),
+            None => (args, &[][..]),
+        };
+        let mut board = match setup.split_first() {
+            Some((&"startpos", _)) => Board::variant_start(self.board.variant()),
+            Some((&"fen", fen)) => Board::from_variant_fen(&fen.join(" "), self.board.variant())
+                .map_err(|error| format!("invalid fen: {}", error))?,
+            _ => return Err("expected startpos or fen".to_string()),
+        };
+        for text in moves {
+            let mv = board
+                .find_move(text)
+                .ok_or_else(|| format!("illegal move {}", text))?;
+            board.make_move(mv);
+        }
+        if self.chess960 {
+            board.set_chess960(true);
+        }
+        self.board = board;
+        Ok(())
+    }
+
+    // Handles `setoption name <name> value <value>`
+    fn set_option(&mut self, args: &[&str]) {
+        let value_at = args.iter().position(|&arg| arg == "value");
+        let name = args[..value_at.unwrap_or(args.len())]
+            .iter()
+            .skip_while(|&&arg| arg == "name")
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let value = value_at.map_or(String::new(), |index| args[index + 1..].join(" "));
+        let number = value.parse::<usize>().ok();
+
+        match (name.to_lowercase().as_str(), number) {
+            ("hash", Some(mb)) => {
+                self.hash_mb = mb.clamp(1, MAX_HASH_MB);
+                self.tt = Arc::new(TranspositionTable::new(self.hash_mb));
+            }
+            ("threads", Some(threads)) => self.threads = threads.clamp(1, MAX_THREADS),
+            ("multipv", Some(lines)) => self.multi_pv = lines.clamp(1, MAX_MULTI_PV),
+            ("uci_chess960", _) if value == "true" || value == "false" => {
+                self.chess960 = value == "true";
+                self.board
+                    .set_chess960(self.chess960 || self.board.is_chess960());
+            }
+            _ => self.send(&format!("info string unknown option {}", name)),
+        }
+    }
+
+    fn go(&mut self, go: GoCommand) {
+        self.stop();
+
+        let mut searcher =
+            Searcher::with_table(go.limits(self.board.side_to_move()), Arc::clone(&self.tt));
+        searcher.set_multi_pv(self.multi_pv);
+        searcher.set_threads(self.threads);
+        let stop = searcher.stop_flag();
+        let mut board = self.board.clone();
+        // Castling rooks never move, so the root position names every move in the line
+        let root = self.board.clone();
+        let output = Arc::clone(&self.output);
+        let tt = Arc::clone(&self.tt);
+        let infinite_stop = Arc::clone(&stop);
+
+        let handle = thread::spawn(move || {
+            let result = searcher.search_with(&mut board, |info| {
+                send(&output, &info_line(&root, info, tt.hashfull()));
+            });
+            // An infinite search only reports its move once told to stop, which also
+            // unparks the thread
+            while go.infinite && !infinite_stop.load(Ordering::Acquire) {
+                thread::park();
+            }
+            let best = result
+                .best_move
+                .map_or("0000".to_string(), |mv| root.move_name(mv));
+            match result.pv.get(1) {
+                Some(&ponder) => send(
+                    &output,
+                    &format!("bestmove {} ponder {}", best, root.move_name(ponder)),
+                ),
+                None => send(&output, &format!("bestmove {}", best)),
+            }
+        });
+        self.search = Some(RunningSearch {
+            stop,
+            handle,
+            infinite: go.infinite,
+        });
+    }
+
+    /// Waits for a running search to finish on its own, stopping it if it is infinite
+    pub fn wait(&mut self) {
+        match &self.search {
+            Some(search) if search.infinite => self.stop(),
+            Some(_) => {
+                if let Some(search) = self.search.take() {
+                    let _ = search.handle.join();
+                }
+            }
+            None => {}
+        }
+    }
+
+    // Stops a running search and waits for it to report its move
+    fn stop(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.stop.store(true, Ordering::Release);
+            search.handle.thread().unpark();
+            let _ = search.handle.join();
+        }
+    }
+
+    fn send(&self, line: &str) {
+        send(&self.output, line);
+    }
+}
+
+pub(crate) fn send<W: Write>(output: &Mutex<W>, line: &str) {
+    let mut output = output
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let _ = writeln!(output, "{}", line);
+    let _ = output.flush();
+}
+
+/// Runs a UCI session until `quit` or the end of the input, where a running search is
+/// allowed to finish so scripted sessions get their result
+pub fn run<R: BufRead, W: Write + Send + 'static>(input: R, output: W) -> io::Result<()> {
+    let mut uci = Uci::new(output);
+    for line in input.lines() {
+        if !uci.handle(&line?) {
+            return Ok(());
+        }
+    }
+    uci.wait();
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use crate::board::{Board, Color, START_FEN};
+use crate::moves::{Move, MoveList};
+
+mod antichess;
+mod atomic;
+mod crazyhouse;
+mod horde;
+mod king_of_the_hill;
+mod racing_kings;
+mod three_check;
+
+pub use antichess::Antichess;
+pub use atomic::Atomic;
+pub use crazyhouse::Crazyhouse;
+pub use horde::Horde;
+pub use king_of_the_hill::KingOfTheHill;
+pub use racing_kings::RacingKings;
+pub use three_check::ThreeCheck;
+
+/// How a finished game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win(Color),
+    Draw,
+}
+
+/// Rules of a chess variant. Each hook defaults to standard chess, so a variant only
+/// overrides what it changes, typically by wrapping the `Board::*_standard_*` methods.
+pub trait Variant: std::fmt::Debug + Send + Sync {
+    /// Gets the name used to select the variant, e.g. "standard"
+    fn name(&self) -> &'static str;
+
+    /// Gets the FEN of the variant's start position
+    fn start_fen(&self) -> &'static str {
+        START_FEN
+    }
+
+    /// Returns true if captured pieces go to a pocket and can be dropped, which adds the
+    /// pockets and promoted piece markers to FEN output
+    fn has_pockets(&self) -> bool {
+        false
+    }
+
+    /// Returns true if checks given are counted, which adds them to FEN output
+    fn counts_checks(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the variant allows castling; boards following a variant without it
+    /// drop any castling rights given in the FEN
+    fn has_castling(&self) -> bool {
+        true
+    }
+
+    /// Returns true if the legal moves are exactly those of standard chess, so the staged
+    /// generators such as `Board::generate_captures` can stand in for `generate_moves`
+    fn has_standard_moves(&self) -> bool {
+        true
+    }
+
+    /// Appends all legal moves for the side to move to the list
+    fn generate_moves(&self, board: &Board, list: &mut MoveList) {
+        board.generate_standard_moves(list);
+    }
+
+    /// Applies a legal move for the side to move
+    fn make_move(&self, board: &mut Board, mv: Move) {
+        board.make_standard_move(mv);
+    }
+
+    /// Reverts the last move applied with `make_move`
+    fn unmake_move(&self, board: &mut Board, mv: Move) {
+        board.unmake_standard_move(mv);
+    }
+
+    /// Returns true if neither side can win however the game goes on, so the position is
+    /// drawn though the game has not ended yet
+    fn is_insufficient_material(&self, board: &Board) -> bool {
+        board.is_insufficient_material()
+    }
+
+    /// Gets the result if the game is over in the position
+    fn outcome(&self, board: &Board) -> Option<Outcome> {
+        board.standard_outcome()
+    }
+}
+
+/// Standard chess, including Chess960 castling
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Standard;
+
+impl Variant for Standard {
+    fn name(&self) -> &'static str {
+        "standard"
+    }
+}
+
+/// All built-in variants
+pub const VARIANTS: [&dyn Variant; 8] = [
+    &Standard,
+    &Crazyhouse,
+    &Atomic,
+    &Antichess,
+    &ThreeCheck,
+    &KingOfTheHill,
+    &Horde,
+    &RacingKings,
+];
+
+/// Looks up a built-in variant by name
+pub fn variant_by_name(name: &str) -> Option<&'static dyn Variant> {
+    VARIANTS
+        .iter()
+        .copied()
+        .find(|variant| variant.name().eq_ignore_ascii_case(name))
+}
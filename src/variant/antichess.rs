@@ -0,0 +1,102 @@
+use crate::attacks::king_attacks;
+use crate::board::{Board, PieceType};
+use crate::moves::{Move, MoveKind, MoveList};
+use crate::variant::{Outcome, Variant};
+
+/// Antichess (losing chess): captures are compulsory, there is no check or castling, the
+/// king is an ordinary piece that pawns may also promote to, and a player wins by losing
+/// all their pieces or being stalemated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Antichess;
+
+impl Variant for Antichess {
+    fn name(&self) -> &'static str {
+        "antichess"
+    }
+
+    fn has_castling(&self) -> bool {
+        false
+    }
+
+    fn has_standard_moves(&self) -> bool {
+        false
+    }
+
+    fn start_fen(&self) -> &'static str {
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1"
+    }
+
+    fn generate_moves(&self, board: &Board, list: &mut MoveList) {
+        let us = board.side_to_move();
+        let kings = board.get_pieces(PieceType::King, us);
+        let start = list.len();
+
+        // Pseudo-legal moves without the king's, which only know about a single king
+        // and castling, then steps for every king the side has
+        let mut moves = MoveList::new();
+        board.generate_pseudo_legal_moves(&mut moves);
+        for &mv in moves.iter() {
+            if kings.test_bit(mv.from()) {
+                continue;
+            }
+            list.push(mv);
+            match mv.kind() {
+                MoveKind::Promotion(PieceType::Queen) => list.push(Move::new(
+                    mv.from(),
+                    mv.to(),
+                    MoveKind::Promotion(PieceType::King),
+                )),
+                MoveKind::PromotionCapture(PieceType::Queen) => list.push(Move::new(
+                    mv.from(),
+                    mv.to(),
+                    MoveKind::PromotionCapture(PieceType::King),
+                )),
+                _ => {}
+            }
+        }
+        let own = board.get_color_pieces(us);
+        let enemy = board.get_color_pieces(us.opposite());
+        for from in kings.squares() {
+            for to in (king_attacks(from) & !own).squares() {
+                let kind = if enemy.test_bit(to) {
+                    MoveKind::Capture
+                } else {
+                    MoveKind::Quiet
+                };
+                list.push(Move::new(from, to, kind));
+            }
+        }
+
+        // Captures are compulsory
+        if (start..list.len()).any(|i| list[i].is_capture()) {
+            let mut i = start;
+            while i < list.len() {
+                if list[i].is_capture() {
+                    i += 1;
+                } else {
+                    list.swap_remove(i);
+                }
+            }
+        }
+    }
+
+    fn is_insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+
+    fn outcome(&self, board: &Board) -> Option<Outcome> {
+        let us = board.side_to_move();
+        if board.get_color_pieces(us).is_empty() {
+            return Some(Outcome::Win(us));
+        }
+        let mut moves = MoveList::new();
+        self.generate_moves(board, &mut moves);
+        if moves.is_empty() {
+            return Some(Outcome::Win(us));
+        }
+        if board.halfmove_clock() >= 100 {
+            return Some(Outcome::Draw);
+        }
+        None
+    }
+}
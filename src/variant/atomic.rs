@@ -0,0 +1,175 @@
+use crate::attacks::king_attacks;
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Color, PieceType};
+use crate::constants::between;
+use crate::moves::{Move, MoveKind, MoveList};
+use crate::variant::{Outcome, Variant};
+
+/// Atomic chess: every capture explodes the capturing piece, the captured piece and all
+/// pieces other than pawns next to the capture square. Kings may not capture, kings
+/// standing next to each other cannot be checked, and blowing up the enemy king wins.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Atomic;
+
+impl Variant for Atomic {
+    fn name(&self) -> &'static str {
+        "atomic"
+    }
+
+    fn has_standard_moves(&self) -> bool {
+        false
+    }
+
+    fn generate_moves(&self, board: &Board, list: &mut MoveList) {
+        let us = board.side_to_move();
+        if board.king_square(us).is_none() || board.king_square(us.opposite()).is_none() {
+            return;
+        }
+
+        let start = list.len();
+        board.generate_pseudo_legal_moves(list);
+        let mut i = start;
+        while i < list.len() {
+            if is_legal(board, list[i]) {
+                i += 1;
+            } else {
+                list.swap_remove(i);
+            }
+        }
+    }
+
+    fn make_move(&self, board: &mut Board, mv: Move) {
+        let blast = if mv.is_capture() {
+            explosion(board, mv)
+        } else {
+            Bitboard::empty()
+        };
+        board.make_standard_move(mv);
+        for square in blast.squares() {
+            board.explode(square);
+        }
+    }
+
+    fn unmake_move(&self, board: &mut Board, mv: Move) {
+        board.restore_exploded();
+        board.unmake_standard_move(mv);
+    }
+
+    fn is_insufficient_material(&self, board: &Board) -> bool {
+        let kings = board.get_pieces(PieceType::King, Color::White)
+            | board.get_pieces(PieceType::King, Color::Black);
+        board.get_all_pieces() == kings
+    }
+
+    fn outcome(&self, board: &Board) -> Option<Outcome> {
+        for color in [Color::White, Color::Black] {
+            if board.king_square(color).is_none() {
+                return Some(Outcome::Win(color.opposite()));
+            }
+        }
+
+        let mut moves = MoveList::new();
+        self.generate_moves(board, &mut moves);
+        if moves.is_empty() {
+            return if is_in_check(board) {
+                Some(Outcome::Win(board.side_to_move().opposite()))
+            } else {
+                Some(Outcome::Draw)
+            };
+        }
+
+        let kings = board.get_pieces(PieceType::King, Color::White)
+            | board.get_pieces(PieceType::King, Color::Black);
+        if board.halfmove_clock() >= 100 || board.get_all_pieces() == kings {
+            return Some(Outcome::Draw);
+        }
+        None
+    }
+}
+
+// Returns true if the side to move is in check under atomic rules, where a king next to
+// the enemy king is never in check
+fn is_in_check(board: &Board) -> bool {
+    let us = board.side_to_move();
+    match board.king_square(us) {
+        Some(king) => !kings_adjacent(board, king) && board.is_in_check(),
+        None => false,
+    }
+}
+
+// Squares whose pieces are removed after the capture: everything that is not a pawn
+// next to the destination, plus the capturing and captured pieces
+fn explosion(board: &Board, mv: Move) -> Bitboard {
+    let to = mv.to();
+    let pawns = board.get_pieces(PieceType::Pawn, Color::White)
+        | board.get_pieces(PieceType::Pawn, Color::Black);
+    let mut blast = king_attacks(to) & board.get_all_pieces() & !pawns;
+    blast.clear_bit(mv.from());
+    // The mover lands on the destination, where the standard move leaves it
+    blast.set_bit(to);
+    blast
+}
+
+fn kings_adjacent(board: &Board, king: u8) -> bool {
+    let enemy_king = board.get_pieces(PieceType::King, board.side_to_move().opposite());
+    !(king_attacks(king) & enemy_king).is_empty()
+}
+
+// Checks a pseudo-legal move against the atomic rules without making it
+fn is_legal(board: &Board, mv: Move) -> bool {
+    let us = board.side_to_move();
+    let them = us.opposite();
+    let king = match board.king_square(us) {
+        Some(king) => king,
+        None => return false,
+    };
+    let enemy = board.get_color_pieces(them);
+    let enemy_king = board.get_pieces(PieceType::King, them);
+    let (from, to) = (mv.from(), mv.to());
+
+    if mv.is_capture() {
+        if from == king {
+            return false;
+        }
+        let mut removed = explosion(board, mv);
+        removed.set_bit(from);
+        if mv.kind() == MoveKind::EnPassant {
+            removed.set_bit(if us == Color::White { to - 8 } else { to + 8 });
+        }
+        if removed.test_bit(king) {
+            return false;
+        }
+        if !(removed & enemy_king).is_empty() {
+            return true;
+        }
+        let occupied = board.get_all_pieces() & !removed;
+        return kings_adjacent(board, king)
+            || (board.attackers_to(king, occupied) & enemy & !removed).is_empty();
+    }
+
+    if mv.is_castle() {
+        // The king may not pass through or land on an attacked square, except next to the
+        // enemy king, and the rook's new square may not expose it either
+        let (rook_from, rook_to) =
+            board.castling_rook_squares(us, mv.kind() == MoveKind::KingCastle);
+        let exempt = enemy_king.lsb().map_or(Bitboard::empty(), king_attacks);
+        let mut occupied = board.get_all_pieces();
+        occupied.clear_bit(from);
+        let mut path = Bitboard::from_u64(between(from, to));
+        path.set_bit(from);
+        let path_safe = (path & !exempt)
+            .squares()
+            .all(|square| (board.attackers_to(square, occupied) & enemy).is_empty());
+        occupied.clear_bit(rook_from);
+        occupied.set_bit(rook_to);
+        return path_safe
+            && (exempt.test_bit(to) || (board.attackers_to(to, occupied) & enemy).is_empty());
+    }
+
+    let king_after = if from == king { to } else { king };
+    let mut occupied = board.get_all_pieces();
+    occupied.clear_bit(from);
+    occupied.set_bit(to);
+    kings_adjacent(board, king_after)
+        || (board.attackers_to(king_after, occupied) & enemy).is_empty()
+}
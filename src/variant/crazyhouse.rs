@@ -0,0 +1,73 @@
+use crate::board::{Board, PieceType};
+use crate::moves::{Move, MoveList};
+use crate::variant::{Outcome, Variant};
+
+/// Crazyhouse: captured pieces join the capturer's pocket and may be dropped back onto
+/// the board instead of moving. Promoted pieces return to the pocket as pawns.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Crazyhouse;
+
+impl Variant for Crazyhouse {
+    fn name(&self) -> &'static str {
+        "crazyhouse"
+    }
+
+    fn has_standard_moves(&self) -> bool {
+        false
+    }
+
+    fn start_fen(&self) -> &'static str {
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1"
+    }
+
+    fn has_pockets(&self) -> bool {
+        true
+    }
+
+    fn generate_moves(&self, board: &Board, list: &mut MoveList) {
+        board.generate_standard_moves(list);
+        board.generate_drops(list);
+    }
+
+    fn make_move(&self, board: &mut Board, mv: Move) {
+        let us = board.side_to_move();
+        board.make_standard_move(mv);
+        if let Some((captured, promoted)) = board.last_capture() {
+            board.add_to_pocket(us, pocket_piece(captured, promoted));
+        }
+    }
+
+    fn unmake_move(&self, board: &mut Board, mv: Move) {
+        let us = board.side_to_move().opposite();
+        if let Some((captured, promoted)) = board.last_capture() {
+            board.remove_from_pocket(us, pocket_piece(captured, promoted));
+        }
+        board.unmake_standard_move(mv);
+    }
+
+    fn is_insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+
+    fn outcome(&self, board: &Board) -> Option<Outcome> {
+        // Material never runs out while it can be dropped back, so only mate and
+        // stalemate end the game
+        let mut moves = MoveList::new();
+        self.generate_moves(board, &mut moves);
+        if !moves.is_empty() {
+            None
+        } else if board.is_in_check() {
+            Some(Outcome::Win(board.side_to_move().opposite()))
+        } else {
+            Some(Outcome::Draw)
+        }
+    }
+}
+
+fn pocket_piece(captured: PieceType, promoted: bool) -> PieceType {
+    if promoted {
+        PieceType::Pawn
+    } else {
+        captured
+    }
+}
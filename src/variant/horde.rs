@@ -0,0 +1,68 @@
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Color, PieceType};
+use crate::constants::{RANK_1, RANK_8};
+use crate::moves::{Move, MoveKind, MoveList};
+use crate::variant::{Outcome, Variant};
+
+/// Horde: white has 36 pawns and no king against the usual black army. Pawns on their
+/// first rank may also move two squares, without allowing en passant. White wins by
+/// checkmate, black by capturing every white piece.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Horde;
+
+impl Variant for Horde {
+    fn name(&self) -> &'static str {
+        "horde"
+    }
+
+    fn has_standard_moves(&self) -> bool {
+        false
+    }
+
+    fn start_fen(&self) -> &'static str {
+        "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1"
+    }
+
+    fn generate_moves(&self, board: &Board, list: &mut MoveList) {
+        board.generate_standard_moves(list);
+
+        let us = board.side_to_move();
+        let (first_rank, push) = match us {
+            Color::White => (RANK_1, 8i8),
+            Color::Black => (RANK_8, -8i8),
+        };
+        let occupied = board.get_all_pieces();
+        let pawns = board.get_pieces(PieceType::Pawn, us).as_u64() & first_rank;
+        for from in Bitboard::from_u64(pawns).squares() {
+            let one = (from as i8 + push) as u8;
+            let two = (one as i8 + push) as u8;
+            // Encoded as a quiet move, so no en passant square is set
+            let mv = Move::new(from, two, MoveKind::Quiet);
+            if !occupied.test_bit(one) && !occupied.test_bit(two) && board.is_legal(mv) {
+                list.push(mv);
+            }
+        }
+    }
+
+    fn is_insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+
+    fn outcome(&self, board: &Board) -> Option<Outcome> {
+        for color in [Color::White, Color::Black] {
+            if board.get_color_pieces(color).is_empty() {
+                return Some(Outcome::Win(color.opposite()));
+            }
+        }
+
+        let mut moves = MoveList::new();
+        self.generate_moves(board, &mut moves);
+        if moves.is_empty() && board.is_in_check() {
+            Some(Outcome::Win(board.side_to_move().opposite()))
+        } else if moves.is_empty() || board.halfmove_clock() >= 100 {
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+}
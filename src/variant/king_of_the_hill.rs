@@ -0,0 +1,53 @@
+use crate::board::{Board, Color, PieceType};
+use crate::constants::CENTER_SQUARES;
+use crate::moves::MoveList;
+use crate::variant::{Outcome, Variant};
+
+/// King of the Hill: standard chess where bringing the king to one of the four center
+/// squares also wins.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KingOfTheHill;
+
+impl Variant for KingOfTheHill {
+    fn name(&self) -> &'static str {
+        "kingofthehill"
+    }
+
+    fn has_standard_moves(&self) -> bool {
+        false
+    }
+
+    fn generate_moves(&self, board: &Board, list: &mut MoveList) {
+        if king_on_hill(board).is_none() {
+            board.generate_standard_moves(list);
+        }
+    }
+
+    fn is_insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+
+    fn outcome(&self, board: &Board) -> Option<Outcome> {
+        if let Some(color) = king_on_hill(board) {
+            return Some(Outcome::Win(color));
+        }
+
+        // A lone king can still walk to the center, so material never runs out
+        let mut moves = MoveList::new();
+        board.generate_standard_moves(&mut moves);
+        if moves.is_empty() && board.is_in_check() {
+            Some(Outcome::Win(board.side_to_move().opposite()))
+        } else if moves.is_empty() || board.halfmove_clock() >= 100 {
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+}
+
+// Gets the color whose king stands on a center square, if any
+fn king_on_hill(board: &Board) -> Option<Color> {
+    [Color::White, Color::Black]
+        .into_iter()
+        .find(|&color| board.get_pieces(PieceType::King, color).as_u64() & CENTER_SQUARES != 0)
+}
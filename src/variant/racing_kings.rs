@@ -0,0 +1,91 @@
+use crate::attacks::king_attacks;
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Color, PieceType};
+use crate::constants::RANK_8;
+use crate::moves::{Move, MoveKind, MoveList};
+use crate::variant::{Outcome, Variant};
+
+/// Racing Kings: no pawns, and no move may give check. The first king to reach the
+/// eighth rank wins, but when white gets there first black has one move to reach it
+/// as well, which draws.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RacingKings;
+
+impl Variant for RacingKings {
+    fn name(&self) -> &'static str {
+        "racingkings"
+    }
+
+    fn has_standard_moves(&self) -> bool {
+        false
+    }
+
+    fn start_fen(&self) -> &'static str {
+        "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1"
+    }
+
+    fn generate_moves(&self, board: &Board, list: &mut MoveList) {
+        if race_result(board).is_some() {
+            return;
+        }
+        let mut moves = MoveList::new();
+        board.generate_standard_moves(&mut moves);
+        for &mv in moves.iter().filter(|&&mv| !board.gives_check(mv)) {
+            list.push(mv);
+        }
+    }
+
+    fn is_insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+
+    fn outcome(&self, board: &Board) -> Option<Outcome> {
+        if let Some(outcome) = race_result(board) {
+            return Some(outcome);
+        }
+        let mut moves = MoveList::new();
+        self.generate_moves(board, &mut moves);
+        if moves.is_empty() || board.halfmove_clock() >= 100 {
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+}
+
+// Gets the result once a king has reached the eighth rank and the race is decided
+fn race_result(board: &Board) -> Option<Outcome> {
+    let on_goal = |color| board.get_pieces(PieceType::King, color).as_u64() & RANK_8 != 0;
+    match (on_goal(Color::White), on_goal(Color::Black)) {
+        (true, true) => Some(Outcome::Draw),
+        (false, true) => Some(Outcome::Win(Color::Black)),
+        (true, false) if board.side_to_move() == Color::White || !black_can_follow(board) => {
+            Some(Outcome::Win(Color::White))
+        }
+        _ => None,
+    }
+}
+
+// Returns true if the black king can step onto a safe eighth-rank square without
+// giving check
+fn black_can_follow(board: &Board) -> bool {
+    let king = match board.king_square(Color::Black) {
+        Some(king) => king,
+        None => return false,
+    };
+    // The king no longer shields its own square from sliders once it moves
+    let mut occupied = board.get_all_pieces();
+    occupied.clear_bit(king);
+    let white = board.get_color_pieces(Color::White);
+    let targets =
+        king_attacks(king) & Bitboard::from_u64(RANK_8) & !board.get_color_pieces(Color::Black);
+    targets.squares().any(|square| {
+        let kind = if white.test_bit(square) {
+            MoveKind::Capture
+        } else {
+            MoveKind::Quiet
+        };
+        (board.attackers_to(square, occupied) & white).is_empty()
+            && !board.gives_check(Move::new(king, square, kind))
+    })
+}
@@ -0,0 +1,82 @@
+use crate::board::{Board, Color, PieceType};
+use crate::moves::{Move, MoveList};
+use crate::variant::{Outcome, Variant};
+
+/// Three-check: standard chess where giving check for the third time also wins. Checks
+/// given are written after the other FEN fields as "+white+black".
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreeCheck;
+
+impl Variant for ThreeCheck {
+    fn name(&self) -> &'static str {
+        "threecheck"
+    }
+
+    fn has_standard_moves(&self) -> bool {
+        false
+    }
+
+    fn start_fen(&self) -> &'static str {
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +0+0"
+    }
+
+    fn counts_checks(&self) -> bool {
+        true
+    }
+
+    fn generate_moves(&self, board: &Board, list: &mut MoveList) {
+        if third_check(board).is_none() {
+            board.generate_standard_moves(list);
+        }
+    }
+
+    fn make_move(&self, board: &mut Board, mv: Move) {
+        let us = board.side_to_move();
+        board.make_standard_move(mv);
+        if board.is_in_check() {
+            board.add_check(us);
+        }
+    }
+
+    fn unmake_move(&self, board: &mut Board, mv: Move) {
+        if board.is_in_check() {
+            board.remove_check(board.side_to_move().opposite());
+        }
+        board.unmake_standard_move(mv);
+    }
+
+    fn is_insufficient_material(&self, board: &Board) -> bool {
+        let kings = board.get_pieces(PieceType::King, Color::White)
+            | board.get_pieces(PieceType::King, Color::Black);
+        board.get_all_pieces() == kings
+    }
+
+    fn outcome(&self, board: &Board) -> Option<Outcome> {
+        if let Some(color) = third_check(board) {
+            return Some(Outcome::Win(color));
+        }
+
+        // Any piece besides the kings can still give checks, so only bare kings are dead
+        let mut moves = MoveList::new();
+        board.generate_standard_moves(&mut moves);
+        let kings = board.get_pieces(PieceType::King, Color::White)
+            | board.get_pieces(PieceType::King, Color::Black);
+        if moves.is_empty() && board.is_in_check() {
+            Some(Outcome::Win(board.side_to_move().opposite()))
+        } else if moves.is_empty()
+            || board.halfmove_clock() >= 100
+            || board.get_all_pieces() == kings
+        {
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+}
+
+// Gets the color that has given three checks, if any
+fn third_check(board: &Board) -> Option<Color> {
+    [Color::White, Color::Black]
+        .into_iter()
+        .find(|&color| board.checks_given(color) >= 3)
+}
@@ -0,0 +1,509 @@
+use crate::board::{Board, Color};
+use crate::constants::MAX_PLY;
+use crate::moves::{Move, MoveKind, MoveList};
+use crate::search::{mate_distance, SearchLimits, SearchResult, Searcher};
+use crate::tt::{TranspositionTable, DEFAULT_HASH_MB};
+use crate::uci::{send, GoCommand, ENGINE_NAME, MAX_THREADS};
+use crate::variant::{variant_by_name, Outcome, Variant};
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Variant names used by the xboard protocol and the built-in variants they select
+pub const XBOARD_VARIANTS: [(&str, &str); 9] = [
+    ("normal", "standard"),
+    ("fischerandom", "standard"),
+    ("crazyhouse", "crazyhouse"),
+    ("atomic", "atomic"),
+    ("giveaway", "antichess"),
+    ("3check", "threecheck"),
+    ("kingofthehill", "kingofthehill"),
+    ("horde", "horde"),
+    ("racingkings", "racingkings"),
+];
+
+// Score xboard shows as mate in zero; mate in n is sent as this plus n
+const XBOARD_MATE: i32 = 100000;
+
+/// Looks up a variant by its xboard name
+pub fn xboard_variant(name: &str) -> Option<&'static dyn Variant> {
+    XBOARD_VARIANTS
+        .iter()
+        .find(|(xboard, _)| *xboard == name)
+        .and_then(|(_, variant)| variant_by_name(variant))
+}
+
+// Returns true if the text is shaped like a move in coordinate notation, e.g. "e2e4",
+// "e7e8q" or "N@f3", or is a castle, so other commands are answered without waiting for
+// the engine
+fn is_move_text(text: &str) -> bool {
+    let square =
+        |file: &u8, rank: &u8| (b'a'..=b'h').contains(file) && (b'1'..=b'8').contains(rank);
+    match text.as_bytes() {
+        b"O-O" | b"O-O-O" => true,
+        [piece, b'@', file, rank] => b"PNBRQK".contains(piece) && square(file, rank),
+        [from_file, from_rank, to_file, to_rank, promotion @ ..] => {
+            square(from_file, from_rank)
+                && square(to_file, to_rank)
+                && match promotion {
+                    [] => true,
+                    [piece] => b"qrbnk".contains(piece),
+                    _ => false,
+                }
+        }
+        _ => false,
+    }
+}
+
+/// Writes a move as xboard expects it: castling in Fischer random is "O-O" or "O-O-O",
+/// everything else is coordinate notation
+pub fn move_text(board: &Board, mv: Move) -> String {
+    match mv.kind() {
+        MoveKind::KingCastle if board.is_chess960() => "O-O".to_string(),
+        MoveKind::QueenCastle if board.is_chess960() => "O-O-O".to_string(),
+        _ => board.move_name(mv),
+    }
+}
+
+// Finds the legal move written as `move_text` writes it, also accepting the king taking
+// its own rook for a Fischer random castle
+fn parse_move(board: &Board, text: &str) -> Option<Move> {
+    let kind = match text {
+        "O-O" => MoveKind::KingCastle,
+        "O-O-O" => MoveKind::QueenCastle,
+        _ => return board.find_move(text),
+    };
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    moves.iter().copied().find(|mv| mv.kind() == kind)
+}
+
+/// Formats an outcome as an xboard result claim
+pub fn result_line(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Win(Color::White) => "1-0 {White wins}",
+        Outcome::Win(Color::Black) => "0-1 {Black wins}",
+        Outcome::Draw => "1/2-1/2 {Draw}",
+    }
+}
+
+/// Formats a completed iteration of a search from the given position as an xboard
+/// thinking line: depth, score, time in centiseconds, nodes and principal variation
+pub fn thinking_line(board: &Board, result: &SearchResult) -> String {
+    let score = match mate_distance(result.score) {
+        Some(moves) if moves > 0 => XBOARD_MATE + moves,
+        Some(moves) => -XBOARD_MATE + moves,
+        None => result.score,
+    };
+    let mut line = format!(
+        "{} {} {} {}",
+        result.depth,
+        score,
+        result.time.as_millis() / 10,
+        result.nodes
+    );
+    for &mv in &result.pv {
+        line.push_str(&format!(" {}", move_text(board, mv)));
+    }
+    line
+}
+
+/// The time control set by `level` or `st`, five minutes for the game by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    /// Moves per session, or 0 when the base time covers the whole game
+    pub moves_per_session: u32,
+    pub base: Duration,
+    pub increment: Duration,
+    /// Fixed time per move, which takes precedence over the clock
+    pub per_move: Option<Duration>,
+}
+
+impl TimeControl {
+    /// Parses the arguments of `level MPS BASE INC`, where the base is in minutes or
+    /// minutes:seconds and the increment in seconds
+    pub fn parse_level(args: &[&str]) -> Option<TimeControl> {
+        let [moves, base, increment] = args else {
+            return None;
+        };
+        let base = match base.split_once(':') {
+            Some((minutes, seconds)) => {
+                minutes.parse::<u64>().ok()? * 60 + seconds.parse::<u64>().ok()?
+            }
+            None => base.parse::<u64>().ok()? * 60,
+        };
+        let increment = increment.parse::<f64>().ok().filter(|inc| *inc >= 0.0)?;
+        Some(TimeControl {
+            moves_per_session: moves.parse().ok()?,
+            base: Duration::from_secs(base),
+            increment: Duration::from_secs_f64(increment),
+            per_move: None,
+        })
+    }
+}
+
+impl Default for TimeControl {
+    fn default() -> Self {
+        TimeControl {
+            moves_per_session: 0,
+            base: Duration::from_secs(300),
+            increment: Duration::ZERO,
+            per_move: None,
+        }
+    }
+}
+
+// A search for the engine's move running on its own thread
+struct Thinking {
+    stop: Arc<AtomicBool>,
+    reply: Arc<Mutex<Reply>>,
+    handle: JoinHandle<()>,
+}
+
+// Shared between a search thread and the session, so a move is either announced and
+// recorded or, once cancelled, neither
+#[derive(Default)]
+struct Reply {
+    cancelled: bool,
+    played: Option<Move>,
+}
+
+/// An xboard (CECP) engine session. Commands are handled one line at a time; the
+/// engine thinks on a background thread so `?`, `force` and `result` can interrupt it.
+pub struct Xboard<W: Write + Send + 'static> {
+    output: Arc<Mutex<W>>,
+    board: Board,
+    // Whether the game is Fischer random, which xboard plays as a standard variant
+    chess960: bool,
+    // Moves played since the last `new` or `setboard`, for `undo` and `remove`
+    moves: Vec<Move>,
+    // The side the engine plays, or None in force mode
+    engine: Option<Color>,
+    tt: Arc<TranspositionTable>,
+    threads: usize,
+    control: TimeControl,
+    depth: Option<u32>,
+    time: Option<Duration>,
+    opponent_time: Option<Duration>,
+    post: bool,
+    thinking: Option<Thinking>,
+}
+
+impl<W: Write + Send + 'static> Xboard<W> {
+    /// Creates a session writing its responses to `output`
+    pub fn new(output: W) -> Self {
+        Xboard {
+            output: Arc::new(Mutex::new(output)),
+            board: Board::initial(),
+            chess960: false,
+            moves: Vec::new(),
+            engine: Some(Color::Black),
+            tt: Arc::new(TranspositionTable::new(DEFAULT_HASH_MB)),
+            threads: 1,
+            control: TimeControl::default(),
+            depth: None,
+            time: None,
+            opponent_time: None,
+            post: false,
+            thinking: None,
+        }
+    }
+
+    /// Handles one command line, returning false once the session should end
+    pub fn handle(&mut self, line: &str) -> bool {
+        self.collect_move();
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let args = tokens.get(1..).unwrap_or(&[]);
+        match tokens.first().copied() {
+            Some("protover") => self.features(),
+            Some("new") => {
+                self.cancel();
+                self.chess960 = false;
+                self.set_board(Board::initial());
+                self.engine = Some(Color::Black);
+                self.depth = None;
+                self.control.per_move = None;
+                self.tt.clear();
+            }
+            Some("variant") => {
+                self.cancel();
+                match args.first().and_then(|name| xboard_variant(name)) {
+                    Some(variant) => {
+                        self.chess960 = args[0] == "fischerandom";
+                        self.set_board(Board::variant_start(variant));
+                    }
+                    None => self.send(&format!("Error (unsupported variant): {}", line)),
+                }
+            }
+            Some("force") => {
+                self.cancel();
+                self.engine = None;
+            }
+            Some("go") => {
+                self.cancel();
+                self.engine = Some(self.board.side_to_move());
+                self.think();
+            }
+            Some("playother") => {
+                self.cancel();
+                self.engine = Some(self.board.side_to_move().opposite());
+            }
+            Some("usermove") => self.user_move(args.first().copied().unwrap_or("")),
+            Some("setboard") => {
+                self.cancel();
+                match Board::from_variant_fen(&args.join(" "), self.board.variant()) {
+                    Ok(board) => self.set_board(board),
+                    Err(_) => self.send("tellusererror Illegal position"),
+                }
+            }
+            Some("level") => match TimeControl::parse_level(args) {
+                Some(control) => self.control = control,
+                None => self.send(&format!("Error (bad arguments): {}", line)),
+            },
+            Some("st") => {
+                let seconds = args.first().and_then(|seconds| seconds.parse::<f64>().ok());
+                self.control.per_move = seconds.map(Duration::from_secs_f64);
+            }
+            Some("sd") => self.depth = args.first().and_then(|depth| depth.parse().ok()),
+            Some("time") => self.time = parse_centiseconds(args),
+            Some("otim") => self.opponent_time = parse_centiseconds(args),
+            Some("undo") => {
+                self.cancel();
+                self.take_back(1);
+            }
+            Some("remove") => {
+                self.cancel();
+                self.take_back(2);
+            }
+            Some("result") => {
+                self.cancel();
+                self.engine = None;
+            }
+            Some("?") => self.move_now(),
+            Some("ping") => self.send(&format!("pong {}", args.join(" "))),
+            Some("post") => self.post = true,
+            Some("nopost") => self.post = false,
+            Some("memory") => {
+                if let Some(mb) = args.first().and_then(|mb| mb.parse::<usize>().ok()) {
+                    self.cancel();
+                    self.tt = Arc::new(TranspositionTable::new(mb.max(1)));
+                }
+            }
+            Some("cores") => {
+                if let Some(cores) = args.first().and_then(|cores| cores.parse::<usize>().ok()) {
+                    self.cancel();
+                    self.threads = cores.clamp(1, MAX_THREADS);
+                }
+            }
+            Some("quit") => {
+                self.cancel();
+                return false;
+            }
+            Some(
+                "xboard" | "accepted" | "rejected" | "hard" | "easy" | "random" | "computer"
+                | "name" | "rating" | "ics" | "white" | "black" | "draw",
+            )
+            | None => {}
+            // Without the usermove feature moves arrive bare
+            Some(text) if is_move_text(text) => self.user_move(text),
+            Some(_) => self.send(&format!("Error (unknown command): {}", line)),
+        }
+        true
+    }
+
+    fn features(&self) {
+        let variants: Vec<&str> = XBOARD_VARIANTS.iter().map(|(name, _)| *name).collect();
+        self.send(&format!(
+            "feature myname=\"{}\" setboard=1 usermove=1 ping=1 playother=1 memory=1 \
+             smp=1 sigint=0 sigterm=0 colors=0 analyze=0 variants=\"{}\"",
+            ENGINE_NAME,
+            variants.join(",")
+        ));
+        self.send("feature done=1");
+    }
+
+    fn set_board(&mut self, mut board: Board) {
+        if self.chess960 {
+            board.set_chess960(true);
+        }
+        self.board = board;
+        self.moves.clear();
+    }
+
+    // Plays the opponent's move, first letting the engine finish a move it is thinking
+    // about, so a scripted session behaves as if it waited for the reply
+    fn user_move(&mut self, text: &str) {
+        self.wait();
+        let Some(mv) = parse_move(&self.board, text) else {
+            self.send(&format!("Illegal move: {}", text));
+            return;
+        };
+        self.play(mv);
+        if self.engine == Some(self.board.side_to_move()) {
+            self.think();
+        }
+    }
+
+    fn play(&mut self, mv: Move) {
+        self.board.make_move(mv);
+        self.moves.push(mv);
+    }
+
+    fn take_back(&mut self, count: usize) {
+        for _ in 0..count {
+            if let Some(mv) = self.moves.pop() {
+                self.board.unmake_move(mv);
+            }
+        }
+    }
+
+    // Starts searching for the engine's move, unless the game is already over
+    fn think(&mut self) {
+        if let Some(outcome) = self.board.outcome() {
+            self.send(result_line(outcome));
+            return;
+        }
+
+        let mut searcher = Searcher::with_table(self.limits(), Arc::clone(&self.tt));
+        searcher.set_threads(self.threads);
+        let stop = searcher.stop_flag();
+        let reply = Arc::new(Mutex::new(Reply::default()));
+        let mut board = self.board.clone();
+        // Castling rooks never move, so the root position names every move in the line
+        let root = self.board.clone();
+        let output = Arc::clone(&self.output);
+        let thread_reply = Arc::clone(&reply);
+        let post = self.post;
+
+        let handle = thread::spawn(move || {
+            let result = searcher.search_with(&mut board, |info| {
+                if post && info.multipv == 1 {
+                    send(&output, &thinking_line(&root, info));
+                }
+            });
+            let mut reply = thread_reply
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let Some(mv) = result.best_move.filter(|_| !reply.cancelled) else {
+                return;
+            };
+            reply.played = Some(mv);
+            send(&output, &format!("move {}", move_text(&board, mv)));
+            board.make_move(mv);
+            if let Some(outcome) = board.outcome() {
+                send(&output, result_line(outcome));
+            }
+        });
+        self.thinking = Some(Thinking {
+            stop,
+            reply,
+            handle,
+        });
+    }
+
+    fn limits(&self) -> SearchLimits {
+        let mut go = GoCommand {
+            depth: self.depth.map(|depth| depth.min(MAX_PLY as u32 - 1)),
+            movetime: self.control.per_move,
+            ..Default::default()
+        };
+        // Before the first `time` command, think as if the full base time were left
+        let time = match self.time {
+            None if go.depth.is_some() => None,
+            time => time.or(Some(self.control.base)),
+        };
+        if let Some(time) = time {
+            let increment = Some(self.control.increment);
+            let (ours, theirs) = (Some(time), self.opponent_time);
+            let (wtime, btime) = match self.board.side_to_move() {
+                Color::White => (ours, theirs),
+                Color::Black => (theirs, ours),
+            };
+            go.wtime = wtime;
+            go.btime = btime;
+            go.winc = increment;
+            go.binc = increment;
+            let session = self.control.moves_per_session;
+            if session > 0 {
+                let played = self.board.fullmove_number().saturating_sub(1) % session;
+                go.movestogo = Some(session - played);
+            }
+        }
+        go.limits(self.board.side_to_move())
+    }
+
+    // Stops thinking so the best move found so far is played
+    fn move_now(&mut self) {
+        if let Some(thinking) = &self.thinking {
+            thinking.stop.store(true, Ordering::Relaxed);
+        }
+        self.wait();
+    }
+
+    // Stops thinking without playing a move, unless one was already announced
+    fn cancel(&mut self) {
+        if let Some(thinking) = &self.thinking {
+            thinking
+                .reply
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .cancelled = true;
+            thinking.stop.store(true, Ordering::Relaxed);
+        }
+        self.wait();
+    }
+
+    /// Waits for the engine to finish thinking and records the move it played
+    pub fn wait(&mut self) {
+        if let Some(thinking) = self.thinking.take() {
+            let _ = thinking.handle.join();
+            let played = thinking
+                .reply
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .played;
+            if let Some(mv) = played {
+                self.play(mv);
+            }
+        }
+    }
+
+    // Records the engine's move once it has been announced
+    fn collect_move(&mut self) {
+        let announced = self.thinking.as_ref().is_some_and(|thinking| {
+            thinking
+                .reply
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .played
+                .is_some()
+        });
+        if announced {
+            self.wait();
+        }
+    }
+
+    fn send(&self, line: &str) {
+        send(&self.output, line);
+    }
+}
+
+fn parse_centiseconds(args: &[&str]) -> Option<Duration> {
+    let centiseconds = args.first()?.parse::<i64>().ok()?;
+    Some(Duration::from_millis(centiseconds.max(0) as u64 * 10))
+}
+
+/// Runs an xboard session until `quit` or the end of the input, where the engine is
+/// allowed to finish thinking so scripted sessions get its move
+pub fn run<R: BufRead, W: Write + Send + 'static>(input: R, output: W) -> io::Result<()> {
+    let mut xboard = Xboard::new(output);
+    for line in input.lines() {
+        if !xboard.handle(&line?) {
+            return Ok(());
+        }
+    }
+    xboard.wait();
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use crate::board::{Board, Color, PieceType, PIECE_TYPES};
+
+// Largest pocket count with its own key; larger counts share it
+const MAX_POCKET: usize = 16;
+
+/// Random keys for every hashed feature of a position, generated at compile time
+pub struct ZobristKeys {
+    /// Indexed by Color, PieceType and square
+    pub pieces: [[[u64; 64]; 6]; 2],
+    /// Indexed by the castling rights bit set
+    pub castling: [u64; 16],
+    /// Indexed by the file of the en passant square
+    pub en_passant: [u64; 8],
+    pub black_to_move: u64,
+    /// Indexed by Color, PieceType and number of pieces in the pocket
+    pub pockets: [[[u64; MAX_POCKET]; 6]; 2],
+    /// Indexed by Color and number of checks given, capped at three
+    pub checks: [[u64; 4]; 2],
+}
+
+pub static ZOBRIST: ZobristKeys = generate_keys();
+
+// SplitMix64, which is good enough for hash keys and runs in a const fn
+const fn next_key(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const fn generate_keys() -> ZobristKeys {
+    let mut state = 0x2545_F491_4F6C_DD1D;
+    let mut keys = ZobristKeys {
+        pieces: [[[0; 64]; 6]; 2],
+        castling: [0; 16],
+        en_passant: [0; 8],
+        black_to_move: 0,
+        pockets: [[[0; MAX_POCKET]; 6]; 2],
+        checks: [[0; 4]; 2],
+    };
+
+    let mut color = 0;
+    while color < 2 {
+        let mut piece = 0;
+        while piece < 6 {
+            let mut square = 0;
+            while square < 64 {
+                keys.pieces[color][piece][square] = next_key(&mut state);
+                square += 1;
+            }
+            // A count of zero hashes to nothing, so empty pockets match no pockets
+            let mut count = 1;
+            while count < MAX_POCKET {
+                keys.pockets[color][piece][count] = next_key(&mut state);
+                count += 1;
+            }
+            piece += 1;
+        }
+        let mut count = 1;
+        while count < 4 {
+            keys.checks[color][count] = next_key(&mut state);
+            count += 1;
+        }
+        color += 1;
+    }
+
+    // Each castling right has its own key, and a set of rights hashes to their xor
+    let mut rights = [0u64; 4];
+    let mut i = 0;
+    while i < 4 {
+        rights[i] = next_key(&mut state);
+        i += 1;
+    }
+    let mut set = 0;
+    while set < 16 {
+        let mut bit = 0;
+        while bit < 4 {
+            if set & (1 << bit) != 0 {
+                keys.castling[set] ^= rights[bit];
+            }
+            bit += 1;
+        }
+        set += 1;
+    }
+
+    let mut file = 0;
+    while file < 8 {
+        keys.en_passant[file] = next_key(&mut state);
+        file += 1;
+    }
+    keys.black_to_move = next_key(&mut state);
+    keys
+}
+
+/// Gets the key of a piece on a square
+pub fn piece_key(piece_type: PieceType, color: Color, square: u8) -> u64 {
+    ZOBRIST.pieces[color as usize][piece_type as usize][square as usize]
+}
+
+impl Board {
+    /// Gets the Zobrist hash of the position. The piece part is updated incrementally;
+    /// side to move, castling rights, en passant file, pockets and check counts are
+    /// folded in on each call.
+    pub fn hash(&self) -> u64 {
+        let mut hash = self.piece_hash() ^ ZOBRIST.castling[self.castling_rights() as usize];
+        if self.side_to_move() == Color::Black {
+            hash ^= ZOBRIST.black_to_move;
+        }
+        if let Some(square) = self.en_passant_square() {
+            hash ^= ZOBRIST.en_passant[square as usize % 8];
+        }
+        for color in [Color::White, Color::Black] {
+            for piece_type in PIECE_TYPES {
+                let count = (self.pocket(color, piece_type) as usize).min(MAX_POCKET - 1);
+                hash ^= ZOBRIST.pockets[color as usize][piece_type as usize][count];
+            }
+            let checks = (self.checks_given(color) as usize).min(3);
+            hash ^= ZOBRIST.checks[color as usize][checks];
+        }
+        hash
+    }
+}
@@ -0,0 +1,78 @@
+use checkbit::board::{Board, Color};
+use checkbit::moves::MoveList;
+use checkbit::variant::{Antichess, Outcome, Variant};
+
+fn antichess(fen: &str) -> Board {
+    Board::from_variant_fen(fen, &Antichess).unwrap()
+}
+
+fn legal_moves(board: &Board) -> Vec<String> {
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    let mut names: Vec<String> = moves.iter().map(|mv| mv.to_string()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_captures_are_compulsory() {
+    let board = antichess("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 2");
+    assert_eq!(legal_moves(&board), vec!["e4d5"]);
+
+    // The king is an ordinary piece: it may be captured, walk into attacks and must
+    // capture when it can
+    let board = antichess("8/8/8/8/8/2q5/3K4/8 w - - 0 1");
+    assert_eq!(legal_moves(&board), vec!["d2c3"]);
+    let board = antichess("8/8/8/8/8/2r5/8/3K4 w - - 0 1");
+    assert!(legal_moves(&board).contains(&"d1c1".to_string()));
+}
+
+#[test]
+fn test_promotion_to_king_and_no_castling() {
+    let board = antichess("8/4P3/8/8/8/8/8/k7 w - - 0 1");
+    assert_eq!(
+        legal_moves(&board),
+        vec!["e7e8b", "e7e8k", "e7e8n", "e7e8q", "e7e8r"]
+    );
+
+    // Several kings all move, and castling rights are never used
+    let board = antichess("4k3/8/8/8/8/8/8/R3K2K w Q - 0 1");
+    let moves = legal_moves(&board);
+    assert!(moves.contains(&"h1g2".to_string()));
+    assert!(moves.contains(&"e1d2".to_string()));
+    assert!(!moves.contains(&"e1c1".to_string()));
+
+    // Castling rights in the FEN are dropped
+    let board = antichess("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    for color in [Color::White, Color::Black] {
+        assert!(!board.can_castle_kingside(color));
+        assert!(!board.can_castle_queenside(color));
+    }
+    assert_eq!(board.to_fen(), "r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1");
+    let moves = legal_moves(&board);
+    assert!(!moves.contains(&"e1g1".to_string()) && !moves.contains(&"e1c1".to_string()));
+}
+
+#[test]
+fn test_antichess_outcome() {
+    // Losing every piece wins
+    let board = antichess("8/8/8/8/8/8/8/4k3 w - - 0 1");
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+
+    // So does being stalemated
+    let board = antichess("8/8/8/8/8/p7/P7/8 w - - 0 1");
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+
+    let board = antichess(Antichess.start_fen());
+    assert_eq!(board.outcome(), None);
+}
+
+#[test]
+fn test_antichess_perft() {
+    let mut board = antichess(Antichess.start_fen());
+    assert_eq!(board.perft(1), 20);
+    assert_eq!(board.perft(2), 400);
+    assert_eq!(board.perft(3), 8067);
+    assert_eq!(board.perft(4), 153299);
+    assert_eq!(board.to_fen(), Antichess.start_fen());
+}
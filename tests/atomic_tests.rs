@@ -0,0 +1,108 @@
+use checkbit::board::{Board, Color, PieceType};
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::variant::{Atomic, Outcome};
+
+fn atomic(fen: &str) -> Board {
+    Board::from_variant_fen(fen, &Atomic).unwrap()
+}
+
+fn legal_moves(board: &Board) -> Vec<String> {
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    moves.iter().map(|mv| mv.to_string()).collect()
+}
+
+#[test]
+fn test_capture_explodes_neighbours() {
+    // Nxd5 blows up the knight, the captured pawn and the queen and bishop next to d5,
+    // but leaves the pawns on c6 and e6
+    let fen = "4k3/8/2p1p3/2bpq3/8/4N3/8/4K3 w - - 0 1";
+    let mut board = atomic(fen);
+    let capture = Move::new(20, 35, MoveKind::Capture);
+    board.make_move(capture);
+    assert_eq!(board.to_fen(), "4k3/8/2p1p3/8/8/8/8/4K3 b - - 0 1");
+    board.unmake_move(capture);
+    assert_eq!(board.to_fen(), fen);
+
+    // En passant explodes around the destination square
+    let fen = "4k3/8/2n5/3pP3/8/8/8/4K3 w - d6 0 1";
+    let mut board = atomic(fen);
+    let capture = Move::new(36, 43, MoveKind::EnPassant);
+    board.make_move(capture);
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+    board.unmake_move(capture);
+    assert_eq!(board.to_fen(), fen);
+}
+
+#[test]
+fn test_explosions_remove_castling_rights() {
+    let mut board = atomic("r3k2r/8/8/8/8/8/6n1/R3K2R w KQkq - 0 1");
+    board.make_move(Move::new(7, 14, MoveKind::Capture)); // Rxg2 blows up the rook itself
+    assert!(!board.can_castle_kingside(Color::White));
+    assert!(board.can_castle_queenside(Color::White));
+}
+
+#[test]
+fn test_atomic_legality() {
+    // Kings cannot capture
+    let board = atomic("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1");
+    assert!(!legal_moves(&board).contains(&"e1d2".to_string()));
+
+    // A capture next to the own king is illegal, blowing up the enemy king wins even
+    // when in check
+    let board = atomic("4k3/3p4/8/8/8/8/4q3/3QK3 w - - 0 1");
+    let moves = legal_moves(&board);
+    assert!(!moves.contains(&"d1e2".to_string()));
+    assert!(moves.contains(&"d1d7".to_string()));
+    assert!(!moves.contains(&"d1d2".to_string()));
+
+    // Kings next to each other cannot give check, so the rook's attack does not matter
+    let board = atomic("8/8/8/8/8/8/3k4/r3K3 w - - 0 1");
+    assert_eq!(board.outcome(), None);
+    assert!(legal_moves(&board).contains(&"e1e2".to_string()));
+}
+
+#[test]
+fn test_atomic_outcome() {
+    // White blew up the black king
+    let board = atomic("8/8/8/8/8/8/8/4K3 b - - 0 1");
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+
+    let mut board = atomic("4k3/4r3/8/8/8/8/8/4RK2 w - - 0 1");
+    board.make_move(Move::new(4, 52, MoveKind::Capture));
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+    assert!(board.get_pieces(PieceType::King, Color::Black).is_empty());
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    assert!(moves.is_empty());
+}
+
+#[test]
+fn test_atomic_perft() {
+    for (fen, expected) in [
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            [20, 400, 8902, 197326].as_slice(),
+        ),
+        (
+            "rn1qkb1r/p5pp/2p5/3p4/N3P3/5P2/PPP4P/R1BQK3 w Qkq - 0 1",
+            [28, 833, 23353].as_slice(),
+        ),
+        (
+            "8/8/8/8/8/8/2k5/rR4KR w KQ - 0 1",
+            [18, 180, 4364, 61401].as_slice(),
+        ),
+    ] {
+        let mut board = atomic(fen);
+        for (depth, &nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                board.perft(depth as u32 + 1),
+                nodes,
+                "{} depth {}",
+                fen,
+                depth + 1
+            );
+        }
+        assert_eq!(board.to_fen(), atomic(fen).to_fen());
+    }
+}
@@ -1,5 +1,7 @@
+use checkbit::attacks::{bishop_attacks, rook_attacks};
 use checkbit::bitboard::Bitboard;
-use checkbit::constants::{FILE_A, FILE_H, RANK_1, RANK_8};
+use checkbit::board::Color;
+use checkbit::constants::{FILE_A, FILE_D, FILE_H, RANK_1, RANK_8};
 
 #[test]
 fn test_empty_and_full() {
@@ -120,11 +122,124 @@ fn test_bit_patterns() {
         -10, -17, // Down-right
     ];
     for offset in moves {
-        if offset > 0 {
-            bb.set_bit((27 + offset) as u8);
-        } else {
-            bb.set_bit((27 as i8 + offset) as u8);
-        }
+        bb.set_bit((27 + offset) as u8);
     }
     assert_eq!(bb.pop_count(), 9); // Center + 8 knight moves
 }
+
+#[test]
+fn test_fills_and_spans() {
+    let mut bb = Bitboard::empty();
+    bb.set_bit(27); // d4
+    assert_eq!(bb.north_fill().as_u64(), FILE_D & !0xFF_FFFF);
+    assert_eq!(bb.south_fill().as_u64(), FILE_D & 0xFFFF_FFFF);
+    assert_eq!(bb.file_fill().as_u64(), FILE_D);
+    assert_eq!(bb.front_span(Color::White).as_u64(), FILE_D & !0xFFFF_FFFF);
+    assert_eq!(bb.rear_span(Color::White).as_u64(), FILE_D & 0xFF_FFFF);
+    assert_eq!(bb.front_span(Color::Black), bb.rear_span(Color::White));
+
+    assert_eq!(Bitboard::from_u64(RANK_1).north_fill(), Bitboard::full());
+    assert_eq!(
+        Bitboard::from_u64(RANK_8).front_span(Color::White),
+        Bitboard::empty()
+    );
+    assert_eq!(Bitboard::empty().file_fill(), Bitboard::empty());
+}
+
+#[test]
+fn test_occluded_fills() {
+    // A rook on a1 with a blocker on a4 fills a1-a3 north and the whole first rank east
+    let mut rook = Bitboard::empty();
+    rook.set_bit(0);
+    let mut empty = Bitboard::full();
+    empty.clear_bit(0);
+    empty.clear_bit(24);
+    assert_eq!(rook.north_occluded_fill(empty).as_u64(), 0x0001_0101);
+    assert_eq!(rook.east_occluded_fill(empty).as_u64(), RANK_1);
+    assert_eq!(rook.west_occluded_fill(empty), rook);
+    assert_eq!(rook.south_occluded_fill(empty), rook);
+
+    // Fills never wrap around the board edges
+    let mut h_file = Bitboard::empty();
+    h_file.set_bit(7);
+    assert_eq!(h_file.east_occluded_fill(Bitboard::full()), h_file);
+    assert_eq!(h_file.north_east_occluded_fill(Bitboard::full()), h_file);
+    assert_eq!(
+        h_file.north_west_occluded_fill(Bitboard::full()).as_u64(),
+        0x0102_0408_1020_4080
+    );
+}
+
+#[test]
+fn test_occluded_fills_match_slider_attacks() {
+    let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+    for _ in 0..200 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        let occupied = Bitboard::from_u64(seed & seed.rotate_left(17));
+
+        for square in 0..64u8 {
+            let mut slider = Bitboard::empty();
+            slider.set_bit(square);
+            let empty = !occupied;
+            let step = |fill: Bitboard, shift: i32, wrap: u64| {
+                let bits = fill.as_u64();
+                let moved = if shift > 0 {
+                    bits << shift
+                } else {
+                    bits >> -shift
+                };
+                Bitboard::from_u64(moved & !wrap)
+            };
+
+            let rook = step(slider.north_occluded_fill(empty), 8, 0)
+                | step(slider.south_occluded_fill(empty), -8, 0)
+                | step(slider.east_occluded_fill(empty), 1, FILE_A)
+                | step(slider.west_occluded_fill(empty), -1, FILE_H);
+            assert_eq!(rook, rook_attacks(square, occupied), "rook on {}", square);
+
+            let bishop = step(slider.north_east_occluded_fill(empty), 9, FILE_A)
+                | step(slider.north_west_occluded_fill(empty), 7, FILE_H)
+                | step(slider.south_east_occluded_fill(empty), -7, FILE_A)
+                | step(slider.south_west_occluded_fill(empty), -9, FILE_H);
+            assert_eq!(
+                bishop,
+                bishop_attacks(square, occupied),
+                "bishop on {}",
+                square
+            );
+        }
+    }
+}
+
+#[test]
+fn test_transforms() {
+    let mut bb = Bitboard::empty();
+    bb.set_bit(1); // b1
+    bb.set_bit(20); // e3
+
+    let expected = |squares: &[u8]| {
+        let mut bb = Bitboard::empty();
+        for &square in squares {
+            bb.set_bit(square);
+        }
+        bb
+    };
+    assert_eq!(bb.flip_vertical(), expected(&[57, 44])); // b8, e6
+    assert_eq!(bb.mirror_horizontal(), expected(&[6, 19])); // g1, d3
+    assert_eq!(bb.flip_diagonal(), expected(&[8, 34])); // a2, c5
+    assert_eq!(bb.rotate_180(), expected(&[62, 43])); // g8, d6
+
+    // Each transform undoes itself, and two mirrors make a half turn
+    let pattern = Bitboard::from_u64(0x0123_4567_89AB_CDEF);
+    assert_eq!(pattern.flip_vertical().flip_vertical(), pattern);
+    assert_eq!(pattern.mirror_horizontal().mirror_horizontal(), pattern);
+    assert_eq!(pattern.flip_diagonal().flip_diagonal(), pattern);
+    assert_eq!(pattern.rotate_180().rotate_180(), pattern);
+    assert_eq!(
+        pattern.flip_vertical().mirror_horizontal(),
+        pattern.rotate_180()
+    );
+    assert_eq!(Bitboard::from_u64(FILE_A).flip_diagonal().as_u64(), RANK_1);
+}
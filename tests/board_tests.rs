@@ -1,5 +1,5 @@
-use checkbit::board::{Board, Color, PieceType};
-use checkbit::constants::*;
+use checkbit::board::{Board, Color, FenError, PieceType, PositionError, PIECE_TYPES, START_FEN};
+use checkbit::moves::{Move, MoveKind, MoveList};
 
 #[test]
 fn test_initial_board() {
@@ -171,6 +171,27 @@ fn test_pawn_special_moves() {
     assert!(moves.pop_count() > 0); // Should have promotion moves
 }
 
+#[test]
+fn test_pawns_on_their_last_rank() {
+    // Hand-built positions may leave a pawn where it can no longer move
+    let mut board = Board::empty();
+    board.place_piece(PieceType::King, Color::White, 4); // e1
+    board.place_piece(PieceType::King, Color::Black, 60); // e8
+    board.place_piece(PieceType::Pawn, Color::White, 63); // h8
+    board.place_piece(PieceType::Pawn, Color::Black, 0); // a1
+    assert_eq!(board.get_moves(63).pop_count(), 0);
+
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    assert_eq!(moves.len(), 5);
+    assert!(moves.iter().all(|mv| mv.from() == 4));
+
+    let flipped = board.color_flipped();
+    let mut moves = MoveList::new();
+    flipped.generate_moves(&mut moves);
+    assert_eq!(moves.len(), 5);
+}
+
 #[test]
 fn test_piece_capture() {
     let mut board = Board::empty();
@@ -213,3 +234,401 @@ fn test_board_state() {
     assert!(all_pieces.test_bit(27));
     assert!(all_pieces.test_bit(36));
 }
+
+#[test]
+fn test_insufficient_material() {
+    let mut board = Board::empty();
+    board.place_piece(PieceType::King, Color::White, 4); // e1
+    board.place_piece(PieceType::King, Color::Black, 60); // e8
+    assert!(board.is_insufficient_material()); // K vs K
+
+    board.place_piece(PieceType::Knight, Color::White, 27); // d4
+    assert!(board.is_insufficient_material()); // K+N vs K
+
+    board = Board::empty();
+    board.place_piece(PieceType::King, Color::White, 4); // e1
+    board.place_piece(PieceType::King, Color::Black, 60); // e8
+    board.place_piece(PieceType::Bishop, Color::White, 2); // c1 (dark)
+    assert!(board.is_insufficient_material()); // K+B vs K
+
+    board.place_piece(PieceType::Bishop, Color::Black, 61); // f8 (dark)
+    assert!(board.is_insufficient_material()); // Same-colored bishops
+
+    board.place_piece(PieceType::Bishop, Color::Black, 58); // c8 (light)
+    assert!(!board.is_insufficient_material()); // Opposite-colored bishops
+
+    board = Board::empty();
+    board.place_piece(PieceType::King, Color::White, 4); // e1
+    board.place_piece(PieceType::King, Color::Black, 60); // e8
+    board.place_piece(PieceType::Bishop, Color::White, 2); // c1
+    board.place_piece(PieceType::Knight, Color::Black, 58); // c8
+    assert!(!board.is_insufficient_material()); // K+B vs K+N
+}
+
+#[test]
+fn test_sufficient_material() {
+    assert!(!Board::initial().is_insufficient_material());
+    assert!(!Board::initial().is_dead_position());
+
+    for piece_type in [PieceType::Pawn, PieceType::Rook, PieceType::Queen] {
+        let mut board = Board::empty();
+        board.place_piece(PieceType::King, Color::White, 4); // e1
+        board.place_piece(PieceType::King, Color::Black, 60); // e8
+        board.place_piece(piece_type, Color::Black, 35); // d5
+        assert!(!board.is_insufficient_material());
+        assert!(!board.is_dead_position());
+    }
+}
+
+#[test]
+fn test_dead_position() {
+    let mut board = Board::empty();
+    board.place_piece(PieceType::King, Color::White, 4); // e1
+    board.place_piece(PieceType::King, Color::Black, 60); // e8
+    assert!(board.is_dead_position());
+
+    // Blocked pawns that the kings can walk around and win are not dead
+    board.place_piece(PieceType::Pawn, Color::White, 28); // e4
+    board.place_piece(PieceType::Pawn, Color::Black, 36); // e5
+    assert!(!board.is_dead_position());
+
+    // A pawn wall across the board that neither king can pass or attack
+    let board = Board::from_fen("4k3/8/8/1p1p1p1p/pPpPpPpP/P1P1P1P1/8/4K3 w - - 0 1").unwrap();
+    assert!(!board.is_insufficient_material());
+    assert!(board.is_dead_position());
+}
+
+#[test]
+fn test_repetition() {
+    let mut board = Board::initial();
+    let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+    for text in shuffle {
+        assert!(!board.is_repetition());
+        board.make_move(board.find_move(text).unwrap());
+    }
+    assert!(board.is_repetition());
+
+    // A pawn move cannot be taken back, so earlier positions no longer count
+    for text in ["e2e3", "e7e6"].into_iter().chain(shuffle) {
+        board.make_move(board.find_move(text).unwrap());
+    }
+    assert!(board.is_repetition());
+    board.make_move(board.find_move("e1e2").unwrap());
+    assert!(!board.is_repetition());
+}
+
+#[test]
+fn test_fen_round_trip() {
+    assert_eq!(Board::initial().to_fen(), START_FEN);
+
+    for fen in [
+        START_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 12 40",
+    ] {
+        assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+    }
+
+    // Move clocks are optional
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - -").unwrap();
+    assert_eq!(board.side_to_move(), Color::Black);
+    assert_eq!(board.halfmove_clock(), 0);
+    assert_eq!(board.fullmove_number(), 1);
+}
+
+#[test]
+fn test_fen_errors() {
+    assert_eq!(
+        Board::from_fen("8/8/8 w").unwrap_err(),
+        FenError::WrongFieldCount(2)
+    );
+    assert!(matches!(
+        Board::from_fen("8/8/8/8/8/8/8 w - - 0 1"),
+        Err(FenError::InvalidPlacement(_))
+    ));
+    assert!(matches!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K2X w - - 0 1"),
+        Err(FenError::InvalidPlacement(_))
+    ));
+    assert!(matches!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+        Err(FenError::InvalidSideToMove(_))
+    ));
+    assert!(matches!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 w KX - 0 1"),
+        Err(FenError::InvalidCastling(_))
+    ));
+    assert!(matches!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - e4 0 1"),
+        Err(FenError::InvalidEnPassant(_))
+    ));
+    assert!(matches!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - x 1"),
+        Err(FenError::InvalidClock(_))
+    ));
+}
+
+#[test]
+fn test_make_and_unmake_move() {
+    let mut board = Board::initial();
+
+    let e4 = Move::new(12, 28, MoveKind::DoublePawnPush);
+    board.make_move(e4);
+    assert_eq!(board.side_to_move(), Color::Black);
+    assert_eq!(board.en_passant_square(), Some(20)); // e3
+    assert_eq!(
+        board.get_piece_at(28),
+        Some((PieceType::Pawn, Color::White))
+    );
+    assert_eq!(board.get_piece_at(12), None);
+    assert_eq!(
+        board.get_pieces(PieceType::Pawn, Color::White).pop_count(),
+        8
+    );
+
+    let nf6 = Move::new(62, 45, MoveKind::Quiet);
+    board.make_move(nf6);
+    assert_eq!(board.en_passant_square(), None);
+    assert_eq!(board.fullmove_number(), 2);
+    assert_eq!(board.halfmove_clock(), 1);
+
+    board.unmake_move(nf6);
+    board.unmake_move(e4);
+    assert_eq!(board.to_fen(), START_FEN);
+}
+
+#[test]
+fn test_make_special_moves() {
+    // Castling moves the rook and removes castling rights
+    let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    let castle = Move::new(4, 6, MoveKind::KingCastle);
+    board.make_move(castle);
+    assert_eq!(board.get_piece_at(5), Some((PieceType::Rook, Color::White)));
+    assert_eq!(board.get_piece_at(7), None);
+    assert!(!board.can_castle_kingside(Color::White));
+    assert!(!board.can_castle_queenside(Color::White));
+    assert!(board.can_castle_kingside(Color::Black));
+
+    // Capturing a rook removes the opponent's right on that side
+    let capture = Move::new(0, 56, MoveKind::Capture);
+    board.unmake_move(castle);
+    board.make_move(capture);
+    assert!(!board.can_castle_queenside(Color::Black));
+    assert!(board.can_castle_kingside(Color::Black));
+    board.unmake_move(capture);
+    assert_eq!(board.to_fen(), "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+
+    // En passant removes the captured pawn
+    let fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    let ep = Move::new(36, 43, MoveKind::EnPassant);
+    board.make_move(ep);
+    assert_eq!(board.get_piece_at(35), None);
+    assert_eq!(
+        board.get_piece_at(43),
+        Some((PieceType::Pawn, Color::White))
+    );
+    board.unmake_move(ep);
+    assert_eq!(board.to_fen(), fen);
+
+    // Promotion replaces the pawn
+    let fen = "1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    let promotion = Move::new(48, 57, MoveKind::PromotionCapture(PieceType::Queen));
+    board.make_move(promotion);
+    assert_eq!(
+        board.get_piece_at(57),
+        Some((PieceType::Queen, Color::White))
+    );
+    assert_eq!(
+        board.get_pieces(PieceType::Pawn, Color::White).pop_count(),
+        0
+    );
+    board.unmake_move(promotion);
+    assert_eq!(board.to_fen(), fen);
+}
+
+#[test]
+fn test_check_detection() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    assert!(!board.is_in_check());
+    assert!(board.is_square_attacked(56, Color::White)); // a8
+    assert!(!board.is_square_attacked(57, Color::White)); // b8
+
+    let board = Board::from_fen("4k3/8/8/8/1b6/8/8/4K3 w - - 0 1").unwrap();
+    assert!(board.is_in_check());
+    assert_eq!(board.checkers().lsb(), Some(25)); // b4
+    assert_eq!(board.king_square(Color::White), Some(4));
+}
+
+#[test]
+fn test_pins_and_discovered_checks() {
+    // White knight e2 pinned by the rook on e7, black bishop d7 pinned by the bishop on b5
+    let board = Board::from_fen("4k3/3br3/8/1B6/8/8/4N3/4K2R w - - 0 1").unwrap();
+    assert_eq!(board.pinned(Color::White).as_u64(), 1u64 << 12); // e2
+    assert_eq!(board.pinners(Color::Black).as_u64(), 1u64 << 52); // e7
+    assert_eq!(board.pinned(Color::Black).as_u64(), 1u64 << 51); // d7
+    assert_eq!(board.pinners(Color::White).as_u64(), 1u64 << 33); // b5
+
+    // Two pieces between the slider and the king are not pinned
+    let board = Board::from_fen("4r3/8/8/8/4P3/8/4N3/4K2k w - - 0 1").unwrap();
+    assert!(board.pinned(Color::White).is_empty());
+    assert!(board.pinners(Color::Black).is_empty());
+
+    // An enemy piece in the way is not a pin either, but can uncover a check
+    let board = Board::from_fen("4r3/8/8/8/4n3/8/8/4K2k b - - 0 1").unwrap();
+    assert!(board.pinned(Color::White).is_empty());
+    assert_eq!(
+        board.discovered_check_candidates(Color::Black).as_u64(),
+        1u64 << 28
+    ); // e4
+    assert!(board.discovered_check_candidates(Color::White).is_empty());
+
+    // Pinned knights have no moves, pinned sliders can move along the pin
+    let board = Board::from_fen("4r3/8/8/8/8/8/4N3/4K2k w - - 0 1").unwrap();
+    assert_eq!(board.get_moves(12).pop_count(), 0);
+    let board = Board::from_fen("4r3/8/8/8/8/8/4R3/4K2k w - - 0 1").unwrap();
+    assert_eq!(board.get_moves(12).pop_count(), 6); // e3-e8
+}
+
+fn assert_occupancy_consistent(board: &Board) {
+    for square in 0..64u8 {
+        let expected = [Color::White, Color::Black].into_iter().find_map(|color| {
+            PIECE_TYPES
+                .into_iter()
+                .find(|&piece_type| board.get_pieces(piece_type, color).test_bit(square))
+                .map(|piece_type| (piece_type, color))
+        });
+        assert_eq!(board.get_piece_at(square), expected, "square {}", square);
+        for color in [Color::White, Color::Black] {
+            assert_eq!(
+                board.get_color_pieces(color).test_bit(square),
+                expected.is_some_and(|(_, c)| c == color)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_occupancy_and_mailbox_updates() {
+    let mut board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    assert_occupancy_consistent(&board);
+
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    for &mv in moves.iter() {
+        board.make_move(mv);
+        assert_occupancy_consistent(&board);
+        board.unmake_move(mv);
+        assert_occupancy_consistent(&board);
+    }
+
+    let mut board = Board::empty();
+    board.place_piece(PieceType::Knight, Color::White, 27);
+    board.place_piece(PieceType::Rook, Color::Black, 27);
+    assert_eq!(
+        board.get_piece_at(27),
+        Some((PieceType::Rook, Color::Black))
+    );
+    assert_occupancy_consistent(&board);
+    assert_occupancy_consistent(&Board::initial());
+}
+
+#[test]
+fn test_validate_legal_positions() {
+    assert_eq!(Board::initial().validate(), Ok(()));
+    for fen in [
+        START_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1",
+    ] {
+        assert_eq!(Board::from_fen(fen).unwrap().validate(), Ok(()), "{}", fen);
+    }
+}
+
+#[test]
+fn test_validate_errors() {
+    let errors = |fen: &str| Board::from_fen(fen).unwrap().validate().unwrap_err();
+
+    assert_eq!(
+        errors("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+        vec![PositionError::MissingKing(Color::Black)]
+    );
+    assert_eq!(
+        errors("4k3/8/8/8/8/8/8/3KK3 w - - 0 1"),
+        vec![PositionError::MultipleKings(Color::White)]
+    );
+    assert_eq!(
+        errors("P3k3/8/8/8/8/8/8/4K2p w - - 0 1"),
+        vec![
+            PositionError::PawnOnBackRank(7),
+            PositionError::PawnOnBackRank(56)
+        ]
+    );
+    assert_eq!(
+        errors("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1"),
+        vec![PositionError::OpponentInCheck]
+    );
+    assert_eq!(
+        errors("4k3/8/8/3pP3/8/8/8/4K3 w - e6 0 1"),
+        vec![PositionError::InvalidEnPassant(44)]
+    );
+    assert_eq!(
+        errors("4k3/8/8/3pP3/8/8/8/4K3 b - d6 0 1"),
+        vec![PositionError::InvalidEnPassant(43)]
+    );
+    assert_eq!(
+        errors("r3k3/8/8/8/8/8/8/4K2R w KQq - 0 1"),
+        vec![PositionError::InvalidCastlingRights {
+            color: Color::White,
+            kingside: false
+        }]
+    );
+    assert_eq!(
+        errors("4k1r1/8/8/8/8/8/8/R3K2R w KQh - 0 1"),
+        vec![PositionError::InvalidCastlingRights {
+            color: Color::Black,
+            kingside: true
+        }]
+    );
+}
+
+#[test]
+fn test_validate_overlapping_pieces() {
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    board.place_piece(PieceType::Knight, Color::White, 4);
+    assert_eq!(
+        board.validate(),
+        Err(vec![PositionError::OverlappingPieces(4)])
+    );
+}
+
+#[test]
+fn test_color_flipped() {
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w Kq - 3 7")
+            .unwrap();
+    let flipped = board.color_flipped();
+    assert_eq!(
+        flipped.to_fen(),
+        "r3k2r/pppbbppp/2n2q1P/1P2p3/3pn3/BN2PNP1/P1PPQPB1/R3K2R b Qk - 3 7"
+    );
+    assert_eq!(flipped.color_flipped().to_fen(), board.to_fen());
+
+    // En passant squares and Chess960 castling rooks move to the other side
+    let board = Board::from_fen("1r2k2r/8/8/8/3pP3/8/8/R3K1R1 b GAhb e3 0 1").unwrap();
+    let flipped = board.color_flipped();
+    assert_eq!(
+        flipped.to_fen(),
+        "r3k1r1/8/8/3Pp3/8/8/8/1R2K2R w KQkq e6 0 1"
+    );
+    let mut moves = MoveList::new();
+    let mut flipped_moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    flipped.generate_moves(&mut flipped_moves);
+    assert_eq!(moves.len(), flipped_moves.len());
+}
@@ -0,0 +1,216 @@
+use checkbit::board::{Board, Color, PieceType, START_FEN};
+use checkbit::constants::{DARK_SQUARES, LIGHT_SQUARES};
+use checkbit::moves::{MoveKind, MoveList};
+use std::collections::HashSet;
+
+fn perft(fen: &str, depth: u32) -> u64 {
+    let mut board = Board::from_fen(fen).unwrap();
+    let nodes = board.perft(depth);
+    assert_eq!(board.to_fen(), Board::from_fen(fen).unwrap().to_fen());
+    nodes
+}
+
+fn back_rank(board: &Board) -> String {
+    board.to_fen().split('/').next_back().unwrap()[..8].to_string()
+}
+
+#[test]
+fn test_scharnagl_start_positions() {
+    assert_eq!(Board::chess960(518).unwrap().to_fen(), START_FEN);
+    assert_eq!(
+        Board::chess960(0).unwrap().to_fen(),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+    );
+    assert_eq!(back_rank(&Board::chess960(959).unwrap()), "RKRNNQBB");
+    assert!(Board::chess960(960).is_none());
+
+    let mut seen = HashSet::new();
+    for number in 0..960 {
+        let board = Board::chess960(number).unwrap();
+        assert_eq!(board.validate(), Ok(()), "{}", number);
+        assert!(seen.insert(back_rank(&board)));
+
+        let bishops = board.get_pieces(PieceType::Bishop, Color::White).as_u64();
+        assert_eq!((bishops & LIGHT_SQUARES).count_ones(), 1);
+        assert_eq!((bishops & DARK_SQUARES).count_ones(), 1);
+
+        let king = board.king_square(Color::White).unwrap();
+        assert!(board.castling_rook(Color::White, false).unwrap() < king);
+        assert!(board.castling_rook(Color::White, true).unwrap() > king);
+    }
+}
+
+#[test]
+fn test_castling_fen_fields() {
+    // Shredder-FEN file letters are written back as X-FEN where unambiguous
+    let board =
+        Board::from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9")
+            .unwrap();
+    assert_eq!(board.castling_rook(Color::White, true), Some(7));
+    assert_eq!(board.castling_rook(Color::White, false), Some(5));
+    assert_eq!(board.castling_rook(Color::Black, false), Some(61));
+    assert!(board.to_fen().contains(" w KQkq "));
+
+    // An inner rook needs its file to tell it apart from the outer one
+    let fen = "4k3/8/8/8/8/8/8/1R2K1RR w G - 0 1";
+    let board = Board::from_fen(fen).unwrap();
+    assert_eq!(board.castling_rook(Color::White, true), Some(6));
+    assert_eq!(board.to_fen(), fen);
+
+    // K and Q pick the outermost rook on their side of the king
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/1R2K1RR w KQ - 0 1").unwrap();
+    assert_eq!(board.castling_rook(Color::White, true), Some(7));
+    assert_eq!(board.castling_rook(Color::White, false), Some(1));
+    assert!(Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w E - 0 1").is_err());
+}
+
+#[test]
+fn test_chess960_castling_moves() {
+    // King on g1 and rook on h1: castling kingside only moves the rook
+    let fen = "4k3/8/8/8/8/8/8/6KR w K - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    let castle = *moves
+        .iter()
+        .find(|mv| mv.kind() == MoveKind::KingCastle)
+        .unwrap();
+    assert_eq!((castle.from(), castle.to()), (6, 6));
+    board.make_move(castle);
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+    board.unmake_move(castle);
+    assert_eq!(board.to_fen(), fen);
+
+    // A rook on c1 right of the king on b1 castles kingside, ending on g1 and f1
+    let fen = "4k3/8/8/8/8/8/8/1KR5 w C - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    let castle = *moves.iter().find(|mv| mv.is_castle()).unwrap();
+    assert_eq!(castle.kind(), MoveKind::KingCastle);
+    assert_eq!((castle.from(), castle.to()), (1, 6));
+    board.make_move(castle);
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+
+    // King on d1 and rook on c1 swap places when castling queenside
+    let fen = "4k3/8/8/8/8/8/8/2RK4 w Q - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    let castle = *moves.iter().find(|mv| mv.is_castle()).unwrap();
+    assert_eq!((castle.from(), castle.to()), (3, 2));
+    board.make_move(castle);
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/2KR4 b - - 1 1");
+    board.unmake_move(castle);
+    assert_eq!(board.to_fen(), fen);
+
+    // The castling rook shields the king from the rook on a1, so castling is illegal
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/rRK5 w B - 0 1").unwrap();
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    assert!(!moves.iter().any(|mv| mv.is_castle()));
+}
+
+#[test]
+fn test_chess960_castling_notation() {
+    // Castling is written as the king taking its own rook, so it cannot be mistaken for
+    // the king's step to c1
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/RK6 w Q - 0 1").unwrap();
+    assert!(board.is_chess960());
+    assert_eq!(
+        board.find_move("b1a1").unwrap().kind(),
+        MoveKind::QueenCastle
+    );
+    assert_eq!(board.find_move("b1c1").unwrap().kind(), MoveKind::Quiet);
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/6KR w K - 0 1").unwrap();
+    let castle = board.find_move("g1h1").unwrap();
+    assert_eq!(castle.kind(), MoveKind::KingCastle);
+    assert_eq!(board.move_name(castle), "g1h1");
+    assert_eq!(board.find_move("g1g1"), None);
+
+    // Standard chess keeps the king's destination
+    let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    assert!(!board.is_chess960());
+    assert_eq!(
+        board.find_move("e1g1").unwrap().kind(),
+        MoveKind::KingCastle
+    );
+
+    // Every move of every start position, with the pieces between king and rooks
+    // removed so the castles are legal, reads back as itself
+    for number in 0..960 {
+        let start = Board::chess960(number).unwrap().to_fen();
+        let (placement, rest) = start.split_once(' ').unwrap();
+        let placement: String = placement
+            .chars()
+            .map(|c| match c {
+                'n' | 'b' | 'q' | 'N' | 'B' | 'Q' => '1',
+                c => c,
+            })
+            .collect();
+        for side in ["w", "b"] {
+            let fen = format!("{} {}{}", placement, side, &rest[1..]);
+            let mut board = Board::from_fen(&fen).unwrap();
+            board.set_chess960(true);
+            let mut moves = MoveList::new();
+            board.generate_moves(&mut moves);
+            assert!(moves.iter().any(|mv| mv.is_castle()), "{}", fen);
+            for &mv in moves.iter() {
+                let name = board.move_name(mv);
+                assert_eq!(board.find_move(&name), Some(mv), "{} in {}", name, start);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_chess960_perft() {
+    for (fen, expected) in [
+        (
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            [21, 528, 12189],
+        ),
+        (
+            "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+            [21, 807, 18002],
+        ),
+        (
+            "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+            [20, 479, 10471],
+        ),
+        (
+            "qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9",
+            [22, 593, 13440],
+        ),
+        (
+            "1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9",
+            [28, 1120, 31058],
+        ),
+        (
+            "qnbnr1kr/ppp1b1pp/4p3/3p1p2/8/2NPP3/PPP1BPPP/QNB1R1KR w HEhe - 1 9",
+            [29, 899, 26578],
+        ),
+    ] {
+        for (depth, &nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                perft(fen, depth as u32 + 1),
+                nodes,
+                "{} depth {}",
+                fen,
+                depth + 1
+            );
+        }
+    }
+}
+
+#[test]
+fn test_chess960_perft_depth_4() {
+    assert_eq!(
+        perft(
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            4
+        ),
+        326672
+    );
+}
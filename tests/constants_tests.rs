@@ -113,28 +113,28 @@ fn test_rank_relationships() {
 fn test_center_squares() {
     // Test center squares definition
     assert_eq!(CENTER_SQUARES.count_ones(), 4); // Should be exactly 4 center squares
-    assert!(CENTER_SQUARES & (1u64 << 27) != 0); // d4
-    assert!(CENTER_SQUARES & (1u64 << 28) != 0); // e4
-    assert!(CENTER_SQUARES & (1u64 << 35) != 0); // d5
-    assert!(CENTER_SQUARES & (1u64 << 36) != 0); // e5
+    assert_ne!(CENTER_SQUARES & (1u64 << 27), 0); // d4
+    assert_ne!(CENTER_SQUARES & (1u64 << 28), 0); // e4
+    assert_ne!(CENTER_SQUARES & (1u64 << 35), 0); // d5
+    assert_ne!(CENTER_SQUARES & (1u64 << 36), 0); // e5
 
     // Test extended center
     assert_eq!(EXTENDED_CENTER.count_ones(), 16); // Should be 16 squares
-    assert!(EXTENDED_CENTER & CENTER_SQUARES == CENTER_SQUARES); // Should include center squares
+    assert_eq!(EXTENDED_CENTER & CENTER_SQUARES, CENTER_SQUARES); // Should include center squares
 }
 
 #[test]
 fn test_castling_squares() {
     // Test kingside castling squares
     assert_eq!(KINGSIDE_SQUARES.count_ones(), 2); // f1 and g1 for white
-    assert!(KINGSIDE_SQUARES & (1u64 << 5) != 0); // f1
-    assert!(KINGSIDE_SQUARES & (1u64 << 6) != 0); // g1
+    assert_ne!(KINGSIDE_SQUARES & (1u64 << 5), 0); // f1
+    assert_ne!(KINGSIDE_SQUARES & (1u64 << 6), 0); // g1
 
     // Test queenside castling squares
     assert_eq!(QUEENSIDE_SQUARES.count_ones(), 3); // b1, c1, and d1 for white
-    assert!(QUEENSIDE_SQUARES & (1u64 << 1) != 0); // b1
-    assert!(QUEENSIDE_SQUARES & (1u64 << 2) != 0); // c1
-    assert!(QUEENSIDE_SQUARES & (1u64 << 3) != 0); // d1
+    assert_ne!(QUEENSIDE_SQUARES & (1u64 << 1), 0); // b1
+    assert_ne!(QUEENSIDE_SQUARES & (1u64 << 2), 0); // c1
+    assert_ne!(QUEENSIDE_SQUARES & (1u64 << 3), 0); // d1
 }
 
 #[test]
@@ -174,3 +174,64 @@ fn test_initial_piece_positions() {
     assert_eq!(BLACK_QUEENS_INIT & RANK_8, BLACK_QUEENS_INIT);
     assert_eq!(BLACK_KING_INIT & RANK_8, BLACK_KING_INIT);
 }
+
+#[test]
+fn test_square_colors() {
+    assert_eq!(LIGHT_SQUARES & DARK_SQUARES, 0);
+    assert_eq!(LIGHT_SQUARES | DARK_SQUARES, !0);
+    assert_eq!(LIGHT_SQUARES.count_ones(), 32);
+    assert_ne!(DARK_SQUARES & 1, 0); // a1 is dark
+    assert_ne!(DARK_SQUARES & (1u64 << 63), 0); // h8 is dark
+    assert_ne!(LIGHT_SQUARES & (1u64 << 7), 0); // h1 is light
+    assert_ne!(LIGHT_SQUARES & (1u64 << 56), 0); // a8 is light
+}
+
+#[test]
+fn test_diagonal_rays() {
+    // a1 towards h8
+    assert_eq!(NORTH_EAST_RAY[0], 0x8040201008040200);
+    assert_eq!(SOUTH_WEST_RAY[63], 0x0040201008040201);
+    // h1 towards a8
+    assert_eq!(NORTH_WEST_RAY[7], 0x0102040810204000);
+    assert_eq!(SOUTH_EAST_RAY[56], 0x0002040810204080);
+    // Rays end at the board edge
+    assert_eq!(NORTH_EAST_RAY[7], 0);
+    assert_eq!(SOUTH_WEST_RAY[8], 0);
+    assert_eq!(NORTH_EAST_RAY[28].count_ones(), 3); // e4 -> f5, g6, h7
+}
+
+#[test]
+fn test_between_and_line() {
+    // a1 and h8 on the long diagonal
+    assert_eq!(between(0, 63), 0x0040201008040200);
+    assert_eq!(between(63, 0), between(0, 63));
+    assert_eq!(line(0, 63), 0x8040201008040201);
+
+    // e1 and e8 on a file
+    assert_eq!(between(4, 60), FILE_E & !RANK_1 & !RANK_8);
+    assert_eq!(line(4, 60), FILE_E);
+    assert_eq!(line(12, 20), FILE_E);
+
+    // a1 and d1 on a rank
+    assert_eq!(between(0, 3), 0x06);
+    assert_eq!(line(0, 3), RANK_1);
+
+    // Adjacent squares have nothing between them but still share a line
+    assert_eq!(between(27, 36), 0);
+    assert_ne!(line(27, 36), 0);
+
+    // Unaligned squares and identical squares
+    assert_eq!(between(0, 17), 0);
+    assert_eq!(line(0, 17), 0);
+    assert_eq!(between(10, 10), 0);
+    assert_eq!(line(10, 10), 0);
+
+    // Every aligned pair is symmetric and between lies on the line
+    for a in 0..64u8 {
+        for b in 0..64u8 {
+            assert_eq!(between(a, b), between(b, a));
+            assert_eq!(line(a, b), line(b, a));
+            assert_eq!(between(a, b) & !line(a, b), 0);
+        }
+    }
+}
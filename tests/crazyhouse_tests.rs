@@ -0,0 +1,129 @@
+use checkbit::board::{Board, Color, PieceType};
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::variant::{Crazyhouse, Outcome};
+
+fn crazyhouse(fen: &str) -> Board {
+    Board::from_variant_fen(fen, &Crazyhouse).unwrap()
+}
+
+fn drops(board: &Board) -> Vec<Move> {
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    moves.iter().copied().filter(|mv| mv.is_drop()).collect()
+}
+
+#[test]
+fn test_crazyhouse_fen() {
+    let board = Board::variant_start(&Crazyhouse);
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1"
+    );
+
+    let fen = "r1bqk2r/pppp1ppp/2n5/4p3/1bB1P3/5Q~2/PPPP1PPP/RNB1K2R[NPpp] w KQkq - 0 6";
+    let board = crazyhouse(fen);
+    assert_eq!(board.pocket(Color::White, PieceType::Knight), 1);
+    assert_eq!(board.pocket(Color::White, PieceType::Pawn), 1);
+    assert_eq!(board.pocket(Color::Black, PieceType::Pawn), 2);
+    assert!(board.promoted().test_bit(21)); // f3
+    assert_eq!(board.to_fen(), fen);
+
+    // The pocket may also be written as a ninth rank
+    let board = crazyhouse("4k3/8/8/8/8/8/8/4K3/Qn w - - 0 1");
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3[Qn] w - - 0 1");
+
+    // Standard boards ignore the extra notation when writing
+    let board = Board::from_fen(fen).unwrap();
+    assert!(!board.to_fen().contains('['));
+}
+
+#[test]
+fn test_drop_moves() {
+    let drop = Move::new(21, 21, MoveKind::Drop(PieceType::Knight));
+    assert_eq!(drop.to_string(), "N@f3");
+    assert_eq!(drop.drop_piece(), Some(PieceType::Knight));
+
+    // A knight may go on any of the 62 empty squares, a pawn not on the back ranks
+    let board = crazyhouse("4k3/8/8/8/8/8/8/4K3[NP] w - - 0 1");
+    let all = drops(&board);
+    let knight_drops = all
+        .iter()
+        .filter(|mv| mv.drop_piece() == Some(PieceType::Knight))
+        .count();
+    assert_eq!(knight_drops, 62);
+    assert_eq!(all.len() - knight_drops, 48);
+
+    // Only blocking drops are legal in check, and none in double check
+    let board = crazyhouse("4k3/8/8/8/8/8/8/r3K3[Q] w - - 0 1");
+    let squares: Vec<u8> = drops(&board).iter().map(|mv| mv.to()).collect();
+    assert_eq!(squares, vec![1, 2, 3]);
+    let board = crazyhouse("4k3/8/8/8/1b6/8/8/r3K3[Q] w - - 0 1");
+    assert!(drops(&board).is_empty());
+
+    // The side to move can only drop its own pieces
+    let board = crazyhouse("4k3/8/8/8/8/8/8/4K3[q] w - - 0 1");
+    assert!(drops(&board).is_empty());
+}
+
+#[test]
+fn test_captures_fill_pockets() {
+    let fen = "4k3/8/8/8/3q~r3/4P3/8/4K3[] w - - 0 1";
+    let mut board = crazyhouse(fen);
+
+    // A promoted queen goes to the pocket as a pawn
+    let capture = Move::new(20, 27, MoveKind::Capture);
+    board.make_move(capture);
+    assert_eq!(board.pocket(Color::White, PieceType::Pawn), 1);
+    assert_eq!(board.pocket(Color::White, PieceType::Queen), 0);
+    board.unmake_move(capture);
+    assert_eq!(board.to_fen(), fen);
+
+    // A regular rook keeps its type
+    let capture = Move::new(20, 28, MoveKind::Capture);
+    board.make_move(capture);
+    assert_eq!(board.pocket(Color::White, PieceType::Rook), 1);
+
+    // Dropping it back empties the pocket, and unmaking restores it
+    let mut board = crazyhouse("4k3/8/8/8/8/8/8/4K3[R] w - - 0 1");
+    let drop = Move::new(0, 0, MoveKind::Drop(PieceType::Rook));
+    board.make_move(drop);
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/R3K3[] b - - 1 1");
+    board.unmake_move(drop);
+    assert_eq!(board.pocket(Color::White, PieceType::Rook), 1);
+}
+
+#[test]
+fn test_promotion_tracking() {
+    let mut board = crazyhouse("4k3/1P6/8/8/8/8/8/4K3[] w - - 0 1");
+    let promotion = Move::new(49, 57, MoveKind::Promotion(PieceType::Queen));
+    board.make_move(promotion);
+    assert!(board.promoted().test_bit(57));
+    assert_eq!(board.to_fen(), "1Q~2k3/8/8/8/8/8/8/4K3[] b - - 0 1");
+
+    // The marker follows the piece when it moves
+    board.make_move(Move::new(60, 52, MoveKind::Quiet));
+    let queen_move = Move::new(57, 1, MoveKind::Quiet);
+    board.make_move(queen_move);
+    assert!(board.promoted().test_bit(1));
+    assert!(!board.promoted().test_bit(57));
+    board.unmake_move(queen_move);
+    assert!(board.promoted().test_bit(57));
+}
+
+#[test]
+fn test_crazyhouse_perft() {
+    let mut board = Board::variant_start(&Crazyhouse);
+    assert_eq!(board.perft(1), 20);
+    assert_eq!(board.perft(2), 400);
+    assert_eq!(board.perft(3), 8902);
+    assert_eq!(board.perft(4), 197281);
+}
+
+#[test]
+fn test_crazyhouse_outcome() {
+    // Back rank mate that a drop on f8 would block is not mate
+    let board = crazyhouse("R5k1/5ppp/8/8/8/8/8/6K1[n] b - - 0 1");
+    assert_eq!(board.outcome(), None);
+    let board = crazyhouse("R5k1/5ppp/8/8/8/8/8/6K1[] b - - 0 1");
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+}
@@ -0,0 +1,94 @@
+use checkbit::board::{Board, Color, PieceType};
+use checkbit::eval::{piece_square, Evaluation, Score, MATERIAL, MAX_PHASE};
+use checkbit::moves::MoveList;
+use checkbit::variant::{Atomic, Crazyhouse};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+// Walks the move tree, checking the incremental terms against a full recomputation
+fn check_incremental(board: &mut Board, depth: u32) {
+    assert_eq!(
+        *board.evaluation(),
+        Evaluation::compute(board),
+        "{}",
+        board.to_fen()
+    );
+    if depth == 0 {
+        return;
+    }
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    for &mv in moves.iter() {
+        board.make_move(mv);
+        check_incremental(board, depth - 1);
+        board.unmake_move(mv);
+    }
+}
+
+#[test]
+fn test_start_position_is_balanced() {
+    let board = Board::initial();
+    let evaluation = board.evaluation();
+    assert_eq!(evaluation.phase, MAX_PHASE);
+    assert_eq!(evaluation.material[0], evaluation.material[1]);
+    assert_eq!(evaluation.total(), Score::new(0, 0));
+    assert_eq!(board.evaluate(), 0);
+}
+
+#[test]
+fn test_terms() {
+    // White is a knight up, so the score flips with the side to move
+    let white = Board::from_fen("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1").unwrap();
+    let black = Board::from_fen("4k3/8/8/8/3N4/8/8/4K3 b - - 0 1").unwrap();
+    assert!(white.evaluate() > 0);
+    assert_eq!(white.evaluate(), -black.evaluate());
+
+    let evaluation = white.evaluation();
+    assert_eq!(evaluation.phase, 1);
+    assert_eq!(
+        evaluation.material[Color::White as usize],
+        MATERIAL[PieceType::Knight as usize]
+    );
+    assert_eq!(
+        evaluation.psqt[Color::White as usize],
+        piece_square(PieceType::Knight, Color::White, 27)
+            + piece_square(PieceType::King, Color::White, 4)
+    );
+    assert_eq!(evaluation.score(Color::White), evaluation.total().taper(1));
+
+    // Mirrored positions score the same for the mirrored side
+    let mirrored = Board::from_fen("4k3/8/8/3n4/8/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(mirrored.evaluate(), white.evaluate());
+}
+
+#[test]
+fn test_taper() {
+    let score = Score::new(100, 20);
+    assert_eq!(score.taper(MAX_PHASE), 100);
+    assert_eq!(score.taper(0), 20);
+    assert_eq!(score.taper(MAX_PHASE / 2), 60);
+    // Extra phase from promoted pieces is capped
+    assert_eq!(score.taper(MAX_PHASE + 4), 100);
+}
+
+#[test]
+fn test_incremental_updates() {
+    check_incremental(&mut Board::from_fen(KIWIPETE).unwrap(), 3);
+    check_incremental(
+        &mut Board::from_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1").unwrap(),
+        3,
+    );
+    check_incremental(&mut Board::variant_start(&Crazyhouse), 3);
+    check_incremental(
+        &mut Board::from_variant_fen(
+            "rn1qkb1r/p5pp/2p5/3p4/N3P3/5P2/PPP4P/R1BQK3 w Qkq - 0 1",
+            &Atomic,
+        )
+        .unwrap(),
+        3,
+    );
+
+    let mut board = Board::empty();
+    board.place_piece(PieceType::Queen, Color::White, 27);
+    assert_eq!(*board.evaluation(), Evaluation::compute(&board));
+}
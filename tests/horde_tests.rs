@@ -0,0 +1,51 @@
+use checkbit::board::{Board, Color};
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::variant::{Horde, Outcome, Variant};
+
+fn legal_moves(board: &Board) -> Vec<String> {
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    moves.iter().map(|mv| mv.to_string()).collect()
+}
+
+#[test]
+fn test_first_rank_double_push() {
+    let board = Board::from_variant_fen("4k3/8/8/8/8/8/8/P6P w - - 0 1", &Horde).unwrap();
+    let moves = legal_moves(&board);
+    assert!(moves.contains(&"a1a3".to_string()));
+    assert!(moves.contains(&"h1h3".to_string()));
+
+    // No en passant square is set, so the pawn cannot be taken in passing
+    let mut board = Board::from_variant_fen("4k3/8/8/8/8/1p6/8/P7 w - - 0 1", &Horde).unwrap();
+    board.make_move(Move::new(0, 16, MoveKind::Quiet));
+    assert_eq!(board.en_passant_square(), None);
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/Pp6/8/8 b - - 0 1");
+
+    // A blocked pawn cannot jump
+    let board = Board::from_variant_fen("4k3/8/8/8/8/8/n7/P7 w - - 0 1", &Horde).unwrap();
+    assert!(legal_moves(&board).is_empty());
+}
+
+#[test]
+fn test_horde_outcome() {
+    let board = Board::variant_start(&Horde);
+    assert_eq!(board.outcome(), None);
+
+    // Black wins by capturing the whole horde
+    let board = Board::from_variant_fen("4k3/8/8/8/8/8/8/8 w - - 0 1", &Horde).unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::Black)));
+
+    // White wins by checkmate
+    let board = Board::from_variant_fen("k7/PP6/PP6/8/8/8/8/8 b - - 0 1", &Horde).unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+}
+
+#[test]
+fn test_horde_perft() {
+    let mut board = Board::variant_start(&Horde);
+    assert_eq!(board.perft(1), 8);
+    assert_eq!(board.perft(2), 128);
+    assert_eq!(board.perft(3), 1274);
+    assert_eq!(board.perft(4), 23310);
+    assert_eq!(board.to_fen(), Horde.start_fen());
+}
@@ -0,0 +1,29 @@
+use checkbit::board::{Board, Color};
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::variant::{KingOfTheHill, Outcome, Standard, Variant};
+
+#[test]
+fn test_king_of_the_hill() {
+    let mut board =
+        Board::from_variant_fen("8/8/4k3/8/8/3K4/8/8 w - - 0 1", &KingOfTheHill).unwrap();
+    assert_eq!(board.outcome(), None);
+    board.make_move(Move::new(19, 28, MoveKind::Quiet)); // Ke4
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    assert!(moves.is_empty());
+
+    // The same material is dead under standard rules
+    let board = Board::from_variant_fen("8/8/4k3/8/8/3K4/8/8 w - - 0 1", &Standard).unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::Draw));
+}
+
+#[test]
+fn test_king_of_the_hill_perft() {
+    let mut board = Board::variant_start(&KingOfTheHill);
+    assert_eq!(board.perft(1), 20);
+    assert_eq!(board.perft(2), 400);
+    assert_eq!(board.perft(3), 8902);
+    assert_eq!(board.perft(4), 197281);
+    assert_eq!(board.to_fen(), KingOfTheHill.start_fen());
+}
@@ -0,0 +1,162 @@
+use checkbit::board::Board;
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::PieceType;
+
+const POSITIONS: [&str; 5] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+];
+
+fn sorted(list: &MoveList) -> Vec<String> {
+    let mut moves: Vec<String> = list.iter().map(|mv| format!("{:?}", mv)).collect();
+    moves.sort();
+    moves
+}
+
+#[test]
+fn test_move_list_basics() {
+    let mut list = MoveList::new();
+    assert!(list.is_empty());
+
+    let e4 = Move::new(12, 28, MoveKind::DoublePawnPush);
+    let nf3 = Move::new(6, 21, MoveKind::Quiet);
+    list.push(e4);
+    list.push(nf3);
+    assert_eq!(list.len(), 2);
+    assert!(list.contains(&nf3));
+    assert_eq!(list[0], e4);
+
+    assert_eq!(list.swap_remove(0), e4);
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0], nf3);
+
+    list.clear();
+    assert!(list.is_empty());
+}
+
+#[test]
+fn test_move_display() {
+    assert_eq!(
+        Move::new(12, 28, MoveKind::DoublePawnPush).to_string(),
+        "e2e4"
+    );
+    assert_eq!(
+        Move::new(52, 61, MoveKind::PromotionCapture(PieceType::Queen)).to_string(),
+        "e7f8q"
+    );
+    assert_eq!(
+        Move::new(52, 60, MoveKind::Promotion(PieceType::Knight)).to_string(),
+        "e7e8n"
+    );
+}
+
+#[test]
+fn test_captures_and_quiets_partition_all_moves() {
+    for fen in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        let mut all = MoveList::new();
+        board.generate_moves(&mut all);
+
+        let mut captures = MoveList::new();
+        board.generate_captures(&mut captures);
+        assert!(captures
+            .iter()
+            .all(|mv| mv.is_capture() || mv.is_promotion()));
+
+        let mut quiets = MoveList::new();
+        board.generate_quiets(&mut quiets);
+        assert!(quiets
+            .iter()
+            .all(|mv| !mv.is_capture() && !mv.is_promotion()));
+
+        // Appending both stages to one list gives exactly the full move list
+        let mut staged = captures.clone();
+        board.generate_quiets(&mut staged);
+        assert_eq!(staged.len(), all.len(), "{}", fen);
+        assert_eq!(sorted(&staged), sorted(&all), "{}", fen);
+    }
+}
+
+#[test]
+fn test_checking_moves() {
+    for fen in POSITIONS {
+        let mut board = Board::from_fen(fen).unwrap();
+        let mut checks = MoveList::new();
+        board.generate_checks(&mut checks);
+
+        let mut quiets = MoveList::new();
+        board.generate_quiets(&mut quiets);
+        for &mv in quiets.iter() {
+            board.make_move(mv);
+            let gives_check = board.is_in_check();
+            board.unmake_move(mv);
+            assert_eq!(checks.contains(&mv), gives_check, "{} {}", fen, mv);
+        }
+    }
+
+    // Castling can give check with the rook
+    let board = Board::from_fen("5k2/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    let mut checks = MoveList::new();
+    board.generate_checks(&mut checks);
+    assert!(checks.contains(&Move::new(4, 6, MoveKind::KingCastle)));
+}
+
+#[test]
+fn test_evasions() {
+    // Not in check: no evasions
+    let board = Board::initial();
+    let mut evasions = MoveList::new();
+    board.generate_evasions(&mut evasions);
+    assert!(evasions.is_empty());
+
+    // Rook check that can be blocked, captured or stepped away from
+    let board = Board::from_fen("4r1k1/8/8/8/8/8/3N4/4K3 w - - 0 1").unwrap();
+    assert!(board.is_in_check());
+    board.generate_evasions(&mut evasions);
+    let mut all = MoveList::new();
+    board.generate_moves(&mut all);
+    assert_eq!(sorted(&evasions), sorted(&all));
+    assert!(evasions.contains(&Move::new(11, 28, MoveKind::Quiet))); // Nd2-e4 blocks
+    assert!(evasions.contains(&Move::new(4, 3, MoveKind::Quiet))); // Ke1-d1
+    assert!(!evasions.contains(&Move::new(4, 12, MoveKind::Quiet))); // Ke1-e2 stays in check
+
+    // Double check: only king moves
+    let board = Board::from_fen("4r1k1/8/8/8/8/5n2/3N4/4K3 w - - 0 1").unwrap();
+    evasions.clear();
+    board.generate_evasions(&mut evasions);
+    assert!(!evasions.is_empty());
+    assert!(evasions.iter().all(|mv| mv.from() == 4));
+
+    // En passant capture of the checking pawn
+    let board = Board::from_fen("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1").unwrap();
+    evasions.clear();
+    board.generate_evasions(&mut evasions);
+    assert!(evasions.contains(&Move::new(28, 19, MoveKind::EnPassant)));
+}
+
+#[test]
+fn test_pinned_pieces_cannot_expose_king() {
+    let board = Board::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    assert!(moves.iter().all(|mv| mv.from() != 12)); // Knight on e2 is pinned
+}
+
+#[test]
+fn test_castling_generation() {
+    let board = Board::from_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1").unwrap();
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    assert!(moves.contains(&Move::new(4, 6, MoveKind::KingCastle)));
+    assert!(moves.contains(&Move::new(4, 2, MoveKind::QueenCastle)));
+
+    // Cannot castle through an attacked square
+    let board = Board::from_fen("r3k2r/8/8/8/2b5/8/8/R3K2R w KQkq - 0 1").unwrap();
+    moves.clear();
+    board.generate_moves(&mut moves);
+    assert!(!moves.contains(&Move::new(4, 6, MoveKind::KingCastle)));
+    assert!(moves.contains(&Move::new(4, 2, MoveKind::QueenCastle)));
+}
@@ -0,0 +1,195 @@
+use checkbit::board::{Board, Color};
+use checkbit::movepick::{mvv_lva, History, MovePicker, MAX_HISTORY};
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::variant::{Antichess, Atomic, Crazyhouse};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+fn position(fen: &str) -> Board {
+    Board::from_fen(fen).unwrap()
+}
+
+fn find(board: &Board, uci: &str) -> Move {
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    *moves.iter().find(|mv| mv.to_string() == uci).unwrap()
+}
+
+fn pick_all(mut picker: MovePicker, board: &Board, history: &History) -> Vec<Move> {
+    let mut picked = Vec::new();
+    while let Some(mv) = picker.next(board, history) {
+        picked.push(mv);
+    }
+    picked
+}
+
+fn sorted(mut moves: Vec<Move>) -> Vec<String> {
+    let mut names: Vec<String> = moves.drain(..).map(|mv| mv.to_string()).collect();
+    names.sort();
+    names
+}
+
+// The picker must return every legal move exactly once, whatever it is told
+fn assert_picks_legal_moves(board: &Board) {
+    let mut legal = MoveList::new();
+    board.generate_moves(&mut legal);
+    let expected = sorted(legal.iter().copied().collect());
+
+    let mut history = History::new();
+    let bogus = Move::new(0, 63, MoveKind::Quiet);
+    let first = legal.iter().next().copied();
+    let last = legal.iter().last().copied();
+    for (i, &mv) in legal.iter().enumerate().take(3) {
+        history.add_killer(1, mv);
+        history.update_butterfly(board.side_to_move(), mv, 100 * i as i32);
+    }
+    history.add_killer(2, bogus);
+    if let Some(last) = last {
+        history.set_countermove(bogus, last);
+    }
+
+    for hash_move in [None, first, last, Some(bogus)] {
+        for ply in [0, 1, 2] {
+            let picker = MovePicker::new(board, hash_move, &history, ply, Some(bogus));
+            let picked = pick_all(picker, board, &history);
+            assert_eq!(sorted(picked), expected, "{}", board.to_fen());
+        }
+    }
+
+    let captures: Vec<Move> = legal
+        .iter()
+        .copied()
+        .filter(|mv| mv.is_capture() || mv.is_promotion())
+        .collect();
+    for hash_move in [None, captures.first().copied(), first] {
+        let picked = pick_all(MovePicker::captures(board, hash_move), board, &history);
+        assert_eq!(
+            sorted(picked),
+            sorted(captures.clone()),
+            "{}",
+            board.to_fen()
+        );
+    }
+}
+
+#[test]
+fn test_picks_every_legal_move_once() {
+    for fen in [
+        checkbit::board::START_FEN,
+        KIWIPETE,
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        // In check, so moves come from the evasion generator
+        "4k3/8/8/8/8/8/3q4/4K3 w - - 0 1",
+    ] {
+        assert_picks_legal_moves(&position(fen));
+    }
+}
+
+#[test]
+fn test_picks_every_legal_move_in_variants() {
+    let crazyhouse = Board::from_variant_fen(
+        "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R[Pp] w KQkq - 0 1",
+        &Crazyhouse,
+    )
+    .unwrap();
+    assert_picks_legal_moves(&crazyhouse);
+    let antichess = Board::from_variant_fen(
+        "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 2",
+        &Antichess,
+    )
+    .unwrap();
+    assert_picks_legal_moves(&antichess);
+    let atomic = Board::from_variant_fen(KIWIPETE, &Atomic).unwrap();
+    assert_picks_legal_moves(&atomic);
+}
+
+#[test]
+fn test_hash_move_comes_first() {
+    let board = position(KIWIPETE);
+    let history = History::new();
+    for uci in ["a2a3", "e5f7", "e1g1"] {
+        let mv = find(&board, uci);
+        let mut picker = MovePicker::new(&board, Some(mv), &history, 0, None);
+        assert_eq!(picker.next(&board, &history), Some(mv));
+        assert_ne!(picker.next(&board, &history), Some(mv));
+    }
+
+    // An illegal hash move, e.g. from a hash collision, is never returned
+    let illegal = Move::new(0, 7, MoveKind::Quiet);
+    let picker = MovePicker::new(&board, Some(illegal), &history, 0, None);
+    assert!(!pick_all(picker, &board, &history).contains(&illegal));
+}
+
+#[test]
+fn test_stage_order() {
+    // The queen can take a free rook, a pawn defended by a pawn, or move quietly
+    let board = position("4k3/8/p7/1p1r4/8/3Q4/8/4K3 w - - 0 1");
+    let mut history = History::new();
+    let killer = find(&board, "e1f1");
+    let counter = find(&board, "d3h7");
+    let previous = Move::new(60, 59, MoveKind::Quiet);
+    history.add_killer(3, killer);
+    history.set_countermove(previous, counter);
+    history.update_butterfly(Color::White, find(&board, "e1e2"), 500);
+
+    let picker = MovePicker::new(&board, None, &history, 3, Some(previous));
+    let picked: Vec<String> = pick_all(picker, &board, &history)
+        .iter()
+        .map(|mv| mv.to_string())
+        .collect();
+    assert_eq!(picked[0], "d3d5");
+    assert_eq!(picked[1], "e1f1");
+    assert_eq!(picked[2], "d3h7");
+    assert_eq!(picked[3], "e1e2");
+    assert_eq!(picked.last().unwrap(), "d3b5");
+}
+
+#[test]
+fn test_captures_by_mvv_lva() {
+    // The pawn can take a rook or a knight
+    let board = position("4k3/8/8/2n1r3/3P4/8/8/3Q1K2 w - - 0 1");
+    let pawn_takes_rook = find(&board, "d4e5");
+    let pawn_takes_knight = find(&board, "d4c5");
+    assert!(mvv_lva(&board, pawn_takes_rook) > mvv_lva(&board, pawn_takes_knight));
+
+    let history = History::new();
+    let picked = pick_all(MovePicker::captures(&board, None), &board, &history);
+    assert_eq!(picked, vec![pawn_takes_rook, pawn_takes_knight]);
+}
+
+#[test]
+fn test_history_updates() {
+    let board = position(KIWIPETE);
+    let mut history = History::new();
+    let cutoff = find(&board, "a2a3");
+    let tried = [find(&board, "b2b3"), find(&board, "g2g3"), cutoff];
+    let previous = Move::new(52, 36, MoveKind::Quiet);
+
+    history.update_quiet_cutoff(Color::White, 4, Some(previous), cutoff, 6, &tried);
+    assert_eq!(history.killers(4), [Some(cutoff), None]);
+    assert_eq!(history.countermove(previous), Some(cutoff));
+    assert!(history.butterfly(Color::White, cutoff) > 0);
+    assert!(history.butterfly(Color::White, tried[0]) < 0);
+    assert_eq!(history.butterfly(Color::Black, cutoff), 0);
+
+    // A repeated killer is not duplicated, and a new one pushes the old one down
+    history.add_killer(4, cutoff);
+    history.add_killer(4, tried[0]);
+    assert_eq!(history.killers(4), [Some(tried[0]), Some(cutoff)]);
+    assert_eq!(history.killers(1000), [None, None]);
+
+    // Scores saturate instead of growing without bound
+    for _ in 0..10_000 {
+        history.update_butterfly(Color::White, cutoff, MAX_HISTORY);
+    }
+    assert!(history.butterfly(Color::White, cutoff) <= MAX_HISTORY);
+
+    history.clear_killers();
+    assert_eq!(history.killers(4), [None, None]);
+    assert!(history.butterfly(Color::White, cutoff) > 0);
+    history.clear();
+    assert_eq!(history.butterfly(Color::White, cutoff), 0);
+    assert_eq!(history.countermove(previous), None);
+}
@@ -0,0 +1,87 @@
+use checkbit::bitboard::Bitboard;
+use checkbit::board::{Board, Color};
+use checkbit::constants::{FILE_A, FILE_B, FILE_C, FILE_D};
+use checkbit::pawns::{attack_spans, front_spans, pawn_islands, rear_spans};
+
+fn squares(list: &[u8]) -> Bitboard {
+    let mut bb = Bitboard::empty();
+    for &square in list {
+        bb.set_bit(square);
+    }
+    bb
+}
+
+#[test]
+fn test_passed_pawns() {
+    // The e7 pawn guards d5's path, while nothing stops h5
+    let board = Board::from_fen("4k3/4p3/8/3P3P/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(board.pawn_structure(Color::White).passed, squares(&[39]));
+    assert!(board.pawn_structure(Color::Black).passed.is_empty());
+}
+
+#[test]
+fn test_isolated_doubled_and_islands() {
+    let board = Board::from_fen("4k3/8/8/8/8/2P5/P1PP4/4K3 w - - 0 1").unwrap();
+    let white = board.pawn_structure(Color::White);
+    assert_eq!(white.isolated, squares(&[8]));
+    assert_eq!(white.doubled, squares(&[10, 18]));
+    assert_eq!(white.islands, vec![squares(&[8]), squares(&[10, 11, 18])]);
+
+    assert_eq!(
+        board.open_files(),
+        Bitboard::from_u64(!(FILE_A | FILE_C | FILE_D))
+    );
+    assert!(white.half_open_files.is_empty());
+    assert_eq!(
+        board.pawn_structure(Color::Black).half_open_files,
+        Bitboard::from_u64(FILE_A | FILE_C | FILE_D)
+    );
+    assert!(pawn_islands(Bitboard::empty()).is_empty());
+}
+
+#[test]
+fn test_backward_pawns() {
+    // d3 cannot advance past c5's attack and e4 is already ahead of it; c5 is in the
+    // same situation against d3
+    let board = Board::from_fen("4k3/8/8/2p5/4P3/3P4/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(board.pawn_structure(Color::White).backward, squares(&[19]));
+    assert_eq!(board.pawn_structure(Color::Black).backward, squares(&[34]));
+
+    // A c2 pawn can still come up to guard d4
+    let board = Board::from_fen("4k3/8/8/2p5/4P3/3P4/2P5/4K3 w - - 0 1").unwrap();
+    assert!(board.pawn_structure(Color::White).backward.is_empty());
+}
+
+#[test]
+fn test_connected_pawns() {
+    // d4 and e4 stand side by side and f3 defends e4
+    let board = Board::from_fen("4k3/8/8/8/3PP3/5P2/P7/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.pawn_structure(Color::White).connected,
+        squares(&[27, 28])
+    );
+}
+
+#[test]
+fn test_spans() {
+    let e4 = squares(&[28]);
+    assert_eq!(front_spans(Color::White, e4), squares(&[36, 44, 52, 60]));
+    assert_eq!(rear_spans(Color::White, e4), squares(&[20, 12, 4]));
+    assert_eq!(
+        attack_spans(Color::White, e4),
+        squares(&[35, 43, 51, 59, 37, 45, 53, 61])
+    );
+    assert_eq!(
+        front_spans(Color::Black, squares(&[49])),
+        Bitboard::from_u64(FILE_B & !(0xFFFF << 48))
+    );
+    assert_eq!(
+        attack_spans(Color::Black, squares(&[48])),
+        Bitboard::from_u64(FILE_B & !(0xFFFF << 48))
+    );
+    assert!(front_spans(Color::White, squares(&[60])).is_empty());
+    assert_eq!(
+        Board::initial().pawn_structure(Color::White).front_spans,
+        Bitboard::from_u64(!0xFFFF)
+    );
+}
@@ -140,3 +140,53 @@ fn test_perft_complex_position() {
 // You would also want to add benchmarks to measure the performance
 // of move generation and track improvements. Consider using
 // Rust's built-in benchmark framework or criterion.rs for this.
+
+// Legal move generation perft against published node counts
+// (https://www.chessprogramming.org/Perft_Results)
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+
+fn legal_perft(fen: &str, depth: u32) -> u64 {
+    let mut board = Board::from_fen(fen).expect("valid FEN");
+    let before = board.to_fen();
+    let nodes = board.perft(depth);
+    assert_eq!(board.to_fen(), before); // make/unmake must restore the position
+    nodes
+}
+
+#[test]
+fn test_legal_perft_initial_position() {
+    let mut board = Board::initial();
+    assert_eq!(board.perft(1), 20);
+    assert_eq!(board.perft(2), 400);
+    assert_eq!(board.perft(3), 8902);
+    assert_eq!(board.perft(4), 197281);
+}
+
+#[test]
+fn test_legal_perft_kiwipete() {
+    assert_eq!(legal_perft(KIWIPETE, 1), 48);
+    assert_eq!(legal_perft(KIWIPETE, 2), 2039);
+    assert_eq!(legal_perft(KIWIPETE, 3), 97862);
+}
+
+#[test]
+fn test_legal_perft_endgame() {
+    assert_eq!(legal_perft(POSITION_3, 1), 14);
+    assert_eq!(legal_perft(POSITION_3, 2), 191);
+    assert_eq!(legal_perft(POSITION_3, 3), 2812);
+    assert_eq!(legal_perft(POSITION_3, 4), 43238);
+}
+
+#[test]
+fn test_legal_perft_promotions() {
+    assert_eq!(legal_perft(POSITION_4, 1), 6);
+    assert_eq!(legal_perft(POSITION_4, 2), 264);
+    assert_eq!(legal_perft(POSITION_4, 3), 9467);
+    assert_eq!(legal_perft(POSITION_5, 1), 44);
+    assert_eq!(legal_perft(POSITION_5, 2), 1486);
+    assert_eq!(legal_perft(POSITION_5, 3), 62379);
+}
@@ -1,10 +1,11 @@
 use checkbit::board::{Board, Color, PieceType};
+use checkbit::moves::MoveList;
 
 // Helper function to setup a position from FEN-like description
 fn setup_position(pieces: &[(&str, PieceType, Color)]) -> Board {
     let mut board = Board::empty();
     for (square_name, piece_type, color) in pieces {
-        let file = square_name.chars().nth(0).unwrap() as u8 - b'a';
+        let file = square_name.chars().next().unwrap() as u8 - b'a';
         let rank = square_name.chars().nth(1).unwrap() as u8 - b'1';
         let square = rank * 8 + file;
         board.place_piece(*piece_type, *color, square);
@@ -186,3 +187,42 @@ fn test_blocked_pawns_position() {
     assert_eq!(white_d_pawn_moves.pop_count(), 0);
     assert_eq!(black_d_pawn_moves.pop_count(), 0);
 }
+
+#[test]
+fn test_positions_are_color_symmetric() {
+    // Small tactical and endgame positions with white to move, in the spirit of those above
+    for fen in [
+        "4k3/8/2n2n2/4p2Q/2B1P3/8/8/4K3 w - - 0 1",
+        "4r3/8/8/8/8/8/4N3/4K3 w - - 0 1",
+        "8/8/8/8/4BR1k/8/8/8 w - - 0 1",
+        "4k3/4P3/8/8/8/8/8/4K3 w - - 0 1",
+        "Rk6/pp6/8/8/8/8/8/8 w - - 0 1",
+        "4k3/8/8/8/3Q4/8/8/4K3 w - - 0 1",
+        "8/8/8/8/8/8/5PPP/5RKR w - - 0 1",
+        "8/8/2k1q3/8/3N4/8/8/8 w - - 0 1",
+        "3k4/8/8/8/3Q3R/8/8/8 w - - 0 1",
+        "8/8/8/3pp3/3PP3/8/8/8 w - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let flipped = board.color_flipped();
+        assert_eq!(flipped.side_to_move(), Color::Black);
+        assert_eq!(flipped.color_flipped().to_fen(), board.to_fen());
+
+        let mut moves = MoveList::new();
+        let mut flipped_moves = MoveList::new();
+        board.generate_moves(&mut moves);
+        flipped.generate_moves(&mut flipped_moves);
+        assert_eq!(moves.len(), flipped_moves.len(), "{}", fen);
+
+        for square in 0..64 {
+            assert_eq!(
+                board.get_moves(square).flip_vertical(),
+                flipped.get_moves(square ^ 56),
+                "{} square {}",
+                fen,
+                square
+            );
+        }
+        assert_eq!(board.evaluate(), flipped.evaluate());
+    }
+}
@@ -0,0 +1,57 @@
+use checkbit::board::{Board, Color};
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::variant::{Outcome, RacingKings, Variant};
+
+fn racing(fen: &str) -> Board {
+    Board::from_variant_fen(fen, &RacingKings).unwrap()
+}
+
+#[test]
+fn test_no_checks() {
+    // Rh7 would check the black king, so it is not generated
+    let board = racing("8/k7/8/8/8/6K1/8/7R w - - 0 1");
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    for mv in moves.iter() {
+        assert!(!board.gives_check(*mv), "{} gives check", mv);
+    }
+    assert!(!moves.contains(&Move::new(7, 55, MoveKind::Quiet)));
+    assert!(moves.contains(&Move::new(7, 15, MoveKind::Quiet)));
+}
+
+#[test]
+fn test_race_to_the_eighth_rank() {
+    // Black can still answer by reaching the eighth rank
+    let mut board = racing("6K1/k7/8/8/8/8/8/8 b - - 0 1");
+    assert_eq!(board.outcome(), None);
+    board.make_move(Move::new(48, 56, MoveKind::Quiet));
+    assert_eq!(board.outcome(), Some(Outcome::Draw));
+
+    // Black cannot follow in time
+    let board = racing("6K1/8/k7/8/8/8/8/8 b - - 0 1");
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    assert!(moves.is_empty());
+
+    // Both goal squares are covered
+    let board = racing("2R3K1/k7/8/8/8/8/8/8 b - - 0 1");
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+
+    // The bishop's diagonal runs on through the king's square to d8
+    let board = racing("6K1/2k1N3/8/B7/8/8/8/1R6 b - - 0 1");
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+
+    let board = racing("k7/8/8/8/8/8/8/6K1 w - - 0 1");
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::Black)));
+}
+
+#[test]
+fn test_racing_kings_perft() {
+    let mut board = Board::variant_start(&RacingKings);
+    assert_eq!(board.perft(1), 21);
+    assert_eq!(board.perft(2), 421);
+    assert_eq!(board.perft(3), 11264);
+    assert_eq!(board.perft(4), 296242);
+    assert_eq!(board.to_fen(), RacingKings.start_fen());
+}
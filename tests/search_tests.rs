@@ -0,0 +1,183 @@
+use checkbit::board::Board;
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::search::{
+    is_mate_score, mate_distance, mate_in, mated_in, SearchLimits, Searcher, MATE,
+};
+use checkbit::tt::TranspositionTable;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn position(fen: &str) -> Board {
+    Board::from_fen(fen).unwrap()
+}
+
+#[test]
+fn test_mate_scores() {
+    assert_eq!(mate_in(1), MATE - 1);
+    assert_eq!(mated_in(2), -MATE + 2);
+    assert!(is_mate_score(mate_in(5)));
+    assert!(is_mate_score(mated_in(5)));
+    assert!(!is_mate_score(900));
+    assert_eq!(mate_distance(mate_in(1)), Some(1));
+    assert_eq!(mate_distance(mate_in(3)), Some(2));
+    assert_eq!(mate_distance(mated_in(2)), Some(-1));
+    assert_eq!(mate_distance(50), None);
+}
+
+#[test]
+fn test_finds_mate_in_one() {
+    // Back rank mate with Ra8#
+    let mut board = position("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    let result = board.search(SearchLimits::depth(3));
+    assert_eq!(result.best_move, Some(Move::new(0, 56, MoveKind::Quiet)));
+    assert_eq!(result.score, mate_in(1));
+    assert_eq!(result.pv, vec![Move::new(0, 56, MoveKind::Quiet)]);
+    assert_eq!(board.to_fen(), "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+}
+
+#[test]
+fn test_finds_mate_in_two() {
+    // The boxed-in king is mated in two, e.g. 1. Rc1 Kb8 2. Rc8#
+    let mut board = position("k7/8/1K6/8/8/8/8/1R6 w - - 0 1");
+    let result = board.search(SearchLimits::depth(4));
+    assert_eq!(result.score, mate_in(3));
+    assert_eq!(mate_distance(result.score), Some(2));
+    assert_eq!(result.pv.len(), 3);
+
+    // The PV is a sequence of legal moves
+    let mut line = board.clone();
+    for &mv in &result.pv {
+        let mut moves = MoveList::new();
+        line.generate_moves(&mut moves);
+        assert!(moves.contains(&mv), "{} is not legal", mv);
+        line.make_move(mv);
+    }
+    assert!(line.is_in_check());
+}
+
+#[test]
+fn test_mated_and_stalemated_positions() {
+    let mut mated = position("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1");
+    let result = mated.search(SearchLimits::depth(2));
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.score, mated_in(0));
+
+    let mut stalemate = position("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+    let result = stalemate.search(SearchLimits::depth(2));
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.score, 0);
+}
+
+#[test]
+fn test_draws() {
+    // Two queens down, white checks forever between h5 and e8
+    let mut board = position("7k/6p1/2K5/8/8/8/4Q3/qq6 w - - 0 1");
+    let result = board.search(SearchLimits::depth(8));
+    assert_eq!(result.score, 0);
+    assert_eq!(result.best_move, Some(Move::new(12, 39, MoveKind::Quiet)));
+
+    // Bare kings are a draw however deep the search goes
+    let start = Instant::now();
+    let mut board = position("8/8/8/4k3/8/8/8/4K3 w - - 0 1");
+    let result = board.search(SearchLimits::depth(101));
+    assert_eq!(result.score, 0);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_wins_material() {
+    // The knight on d5 hangs to the queen
+    let mut board = position("4k3/8/8/3n4/8/8/3Q4/4K3 w - - 0 1");
+    let result = board.search(SearchLimits::depth(3));
+    assert_eq!(result.best_move, Some(Move::new(11, 35, MoveKind::Capture)));
+    assert!(result.score > 200);
+
+    // A checked side cannot stand pat, so the forking check is seen to win the queen
+    let mut board = position("q3k3/8/8/1N6/8/8/8/6K1 w - - 0 1");
+    let result = board.search(SearchLimits::depth(1));
+    assert_eq!(result.best_move, Some(Move::new(33, 50, MoveKind::Quiet)));
+    assert!(result.score > 0);
+
+    // Quiescence sees that taking the defended pawn loses the queen
+    let mut board = position("4k3/2p1p3/3p4/8/8/8/3Q4/4K3 w - - 0 1");
+    let result = board.search(SearchLimits::depth(1));
+    assert_ne!(result.best_move, Some(Move::new(11, 43, MoveKind::Capture)));
+    assert!(result.score > 0);
+}
+
+#[test]
+fn test_limits() {
+    let mut searcher = Searcher::new(SearchLimits::nodes(5000));
+    let result = searcher.search(&mut Board::initial());
+    assert!(result.best_move.is_some());
+    assert!(result.nodes <= 5000);
+
+    let start = Instant::now();
+    let result = Board::initial().search(SearchLimits::time(Duration::from_millis(100)));
+    assert!(result.best_move.is_some());
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    let mut depths = Vec::new();
+    let mut searcher = Searcher::new(SearchLimits::depth(3));
+    let result = searcher.search_with(&mut Board::initial(), |info| depths.push(info.depth));
+    assert_eq!(depths, vec![1, 2, 3]);
+    assert_eq!(result.depth, 3);
+    assert!(result.seldepth >= 3);
+}
+
+#[test]
+fn test_single_thread_is_deterministic() {
+    let search = || {
+        let mut board =
+            position("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3");
+        let mut searcher = Searcher::new(SearchLimits::depth(5));
+        searcher.set_threads(1);
+        searcher.search(&mut board)
+    };
+    let first = search();
+    let second = search();
+    assert_eq!(first.pv, second.pv);
+    assert_eq!(first.score, second.score);
+    assert_eq!(first.nodes, second.nodes);
+}
+
+#[test]
+fn test_lazy_smp() {
+    // Helpers share the table, so the mate is still found and reported in full
+    let mut board = position("k7/8/1K6/8/8/8/8/1R6 w - - 0 1");
+    let tt = Arc::new(TranspositionTable::new(4));
+    let mut searcher = Searcher::with_table(SearchLimits::depth(4), Arc::clone(&tt));
+    searcher.set_threads(4);
+    let mut depths = Vec::new();
+    let result = searcher.search_with(&mut board, |info| depths.push(info.depth));
+    assert_eq!(depths, vec![1, 2, 3, 4]);
+    assert_eq!(result.score, mate_in(3));
+    assert_eq!(board.to_fen(), "k7/8/1K6/8/8/8/8/1R6 w - - 0 1");
+    assert!(tt.probe(board.hash(), 0).is_some());
+
+    // Iterations report the main thread's nodes; the result adds the helpers'
+    let mut searcher = Searcher::new(SearchLimits::depth(5));
+    searcher.set_threads(4);
+    let mut main_nodes = 0;
+    let result = searcher.search_with(&mut Board::initial(), |info| main_nodes = info.nodes);
+    assert!(result.best_move.is_some());
+    assert!(result.nodes > main_nodes);
+
+    // Helpers stop with the main thread
+    let start = Instant::now();
+    let mut searcher = Searcher::new(SearchLimits::time(Duration::from_millis(100)));
+    searcher.set_threads(3);
+    assert!(searcher.search(&mut Board::initial()).best_move.is_some());
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    let mut searcher = Searcher::new(SearchLimits::default());
+    searcher.set_threads(2);
+    let stop = searcher.stop_flag();
+    let stopper = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+    });
+    assert!(searcher.search(&mut Board::initial()).best_move.is_some());
+    stopper.join().unwrap();
+}
@@ -0,0 +1,105 @@
+use checkbit::board::Board;
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::PieceType;
+
+fn see(fen: &str, mv: Move) -> i32 {
+    let board = Board::from_fen(fen).unwrap();
+    let value = board.see(mv);
+    assert!(board.see_ge(mv, value));
+    assert!(!board.see_ge(mv, value + 1));
+    value
+}
+
+#[test]
+fn test_see_simple_captures() {
+    // Undefended pawn
+    let fen = "1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1";
+    assert_eq!(see(fen, Move::new(4, 36, MoveKind::Capture)), 100);
+
+    // Defended pawn taken by a rook
+    let fen = "3rk3/8/8/3p4/8/8/3R4/4K3 w - - 0 1";
+    assert_eq!(see(fen, Move::new(11, 35, MoveKind::Capture)), -400);
+
+    // En passant against an undefended pawn
+    let fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+    assert_eq!(see(fen, Move::new(36, 43, MoveKind::EnPassant)), 100);
+}
+
+#[test]
+fn test_see_xray_attackers() {
+    // The second rook on the file joins the exchange once the first one has captured
+    let fen = "3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1";
+    assert_eq!(see(fen, Move::new(11, 35, MoveKind::Capture)), 100);
+
+    // Long exchange with x-rays on both sides
+    let fen = "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1";
+    assert_eq!(see(fen, Move::new(19, 36, MoveKind::Capture)), -200);
+}
+
+#[test]
+fn test_see_pinned_defender() {
+    // The knight on e7 is pinned to its king and cannot recapture
+    let fen = "4k3/4n3/8/3p4/8/1B6/8/4R1K1 w - - 0 1";
+    assert_eq!(see(fen, Move::new(17, 35, MoveKind::Capture)), 100);
+
+    // Without the pinning rook the bishop is lost for a pawn
+    let fen = "4k3/4n3/8/3p4/8/1B6/8/6K1 w - - 0 1";
+    assert_eq!(see(fen, Move::new(17, 35, MoveKind::Capture)), -200);
+}
+
+#[test]
+fn test_see_quiet_moves_and_promotions() {
+    // Moving the queen onto a square attacked by a pawn hangs it
+    let fen = "4k3/8/8/2p5/8/8/8/3QK3 w - - 0 1";
+    assert_eq!(see(fen, Move::new(3, 27, MoveKind::Quiet)), -900);
+
+    // Safe quiet move
+    assert_eq!(see(fen, Move::new(3, 19, MoveKind::Quiet)), 0);
+
+    // Undefended promotion gains a queen for a pawn
+    let fen = "8/1P2k3/8/8/8/8/8/4K3 w - - 0 1";
+    let promotion = Move::new(49, 57, MoveKind::Promotion(PieceType::Queen));
+    assert_eq!(see(fen, promotion), 800);
+}
+
+#[test]
+fn test_see_king_recaptures() {
+    // The king can take an undefended rook
+    let fen = "4k3/8/8/8/8/8/4r3/4K3 w - - 0 1";
+    assert_eq!(see(fen, Move::new(4, 12, MoveKind::Capture)), 500);
+
+    // The bishop defends d7, so the king cannot recapture the rook
+    let fen = "4k3/3p4/8/1B6/8/8/8/3RK3 w - - 0 1";
+    assert_eq!(see(fen, Move::new(3, 51, MoveKind::Capture)), 100);
+
+    // Without the bishop the rook is lost for a pawn
+    let fen = "4k3/3p4/8/8/8/8/8/3RK3 w - - 0 1";
+    assert_eq!(see(fen, Move::new(3, 51, MoveKind::Capture)), -400);
+}
+
+#[test]
+fn test_see_ge_matches_see() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let mut moves = MoveList::new();
+        board.generate_moves(&mut moves);
+        for &mv in moves.iter() {
+            let value = board.see(mv);
+            for threshold in (-1000..=1000).step_by(50) {
+                assert_eq!(
+                    board.see_ge(mv, threshold),
+                    value >= threshold,
+                    "{} {} {}",
+                    fen,
+                    mv,
+                    threshold
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use checkbit::board::{Board, Color, FenError};
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::variant::{Outcome, ThreeCheck, Variant};
+
+#[test]
+fn test_check_counts_in_fen() {
+    let fen = "rnbqkbnr/ppp2ppp/8/3pp3/4P3/5Q2/PPPP1PPP/RNB1KBNR w KQkq - 0 3 +2+1";
+    let board = Board::from_variant_fen(fen, &ThreeCheck).unwrap();
+    assert_eq!(board.checks_given(Color::White), 2);
+    assert_eq!(board.checks_given(Color::Black), 1);
+    assert_eq!(board.to_fen(), fen);
+
+    // Other variants parse the counts but leave them out of FEN output
+    let board = Board::from_fen(fen).unwrap();
+    assert_eq!(board.checks_given(Color::White), 2);
+    assert!(board.to_fen().ends_with(" 0 3"));
+
+    assert!(matches!(
+        Board::from_fen("8/8/8/8/8/8/8/K1k5 w - - 0 1 +1"),
+        Err(FenError::InvalidCheckCount(_))
+    ));
+}
+
+#[test]
+fn test_third_check_wins() {
+    let fen = "rnbqkbnr/ppp2ppp/8/3pp3/4P3/5Q2/PPPP1PPP/RNB1KBNR w KQkq - 0 3 +2+1";
+    let mut board = Board::from_variant_fen(fen, &ThreeCheck).unwrap();
+    assert_eq!(board.outcome(), None);
+
+    let check = Move::new(21, 61, MoveKind::Capture); // Qxf7+
+    board.make_move(check);
+    assert_eq!(board.checks_given(Color::White), 3);
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    assert!(moves.is_empty());
+
+    board.unmake_move(check);
+    assert_eq!(board.to_fen(), fen);
+
+    // Bare kings are drawn, but a knight can still give checks
+    let board = Board::from_variant_fen("8/8/4k3/8/8/8/8/4K3 w - - 0 1 +0+0", &ThreeCheck).unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::Draw));
+    let board =
+        Board::from_variant_fen("8/8/4k3/8/8/8/8/4KN2 w - - 0 1 +0+0", &ThreeCheck).unwrap();
+    assert_eq!(board.outcome(), None);
+}
+
+#[test]
+fn test_three_check_perft() {
+    let mut board = Board::variant_start(&ThreeCheck);
+    assert_eq!(board.perft(1), 20);
+    assert_eq!(board.perft(2), 400);
+    assert_eq!(board.perft(3), 8902);
+    assert_eq!(board.perft(4), 197281);
+    assert_eq!(board.to_fen(), ThreeCheck.start_fen());
+}
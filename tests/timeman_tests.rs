@@ -0,0 +1,125 @@
+use checkbit::board::Board;
+use checkbit::moves::{Move, MoveKind};
+use checkbit::search::{SearchLimits, SearchResult};
+use checkbit::timeman::{Clock, TimeManager, MOVE_OVERHEAD};
+use std::time::{Duration, Instant};
+
+fn clock(remaining_ms: u64, increment_ms: u64, moves_to_go: Option<u32>) -> Clock {
+    Clock {
+        remaining: Duration::from_millis(remaining_ms),
+        increment: Duration::from_millis(increment_ms),
+        moves_to_go,
+    }
+}
+
+fn iteration(best_move: Move, score: i32) -> SearchResult {
+    SearchResult {
+        best_move: Some(best_move),
+        score,
+        ..Default::default()
+    }
+}
+
+fn e4() -> Move {
+    Move::new(12, 28, MoveKind::DoublePawnPush)
+}
+
+fn d4() -> Move {
+    Move::new(11, 27, MoveKind::DoublePawnPush)
+}
+
+#[test]
+fn test_limits_from_clock() {
+    let time = TimeManager::new(clock(60_000, 1_000, None));
+    let available = Duration::from_millis(60_000) - MOVE_OVERHEAD;
+    assert_eq!(
+        time.soft_limit(),
+        available / 30 + Duration::from_millis(750)
+    );
+    assert_eq!(time.hard_limit(), time.soft_limit() * 5);
+
+    // Fewer moves to go means more time per move, but the hard limit keeps a reserve
+    let time = TimeManager::new(clock(60_000, 0, Some(2)));
+    assert_eq!(time.hard_limit(), available * 4 / 5);
+    assert!(time.soft_limit() <= time.hard_limit());
+
+    // Nearly flagged: no time to think at all
+    let time = TimeManager::new(clock(10, 0, None));
+    assert_eq!(time.hard_limit(), Duration::ZERO);
+    assert!(time.should_stop() && time.out_of_time());
+}
+
+#[test]
+fn test_fixed_time() {
+    let mut time = TimeManager::fixed(Duration::from_millis(500));
+    time.set_legal_moves(1);
+    time.update(&iteration(e4(), 0));
+    time.update(&iteration(d4(), -300));
+    assert_eq!(time.soft_limit(), Duration::from_millis(500));
+    assert_eq!(time.hard_limit(), Duration::from_millis(500));
+    assert!(!time.should_stop());
+}
+
+#[test]
+fn test_forced_move_stops_at_once() {
+    let mut time = TimeManager::new(clock(60_000, 0, None));
+    time.set_legal_moves(1);
+    assert_eq!(time.soft_limit(), Duration::ZERO);
+    assert!(time.should_stop());
+    assert!(!time.out_of_time());
+}
+
+#[test]
+fn test_unstable_best_move_extends() {
+    let mut time = TimeManager::new(clock(60_000, 0, None));
+    let planned = time.soft_limit();
+    time.update(&iteration(e4(), 20));
+    assert_eq!(time.soft_limit(), planned);
+    time.update(&iteration(d4(), 20));
+    time.update(&iteration(e4(), 20));
+    assert!(time.soft_limit() > planned);
+    assert!(time.soft_limit() <= time.hard_limit());
+}
+
+#[test]
+fn test_falling_score_extends() {
+    let mut time = TimeManager::new(clock(60_000, 0, None));
+    let planned = time.soft_limit();
+    time.update(&iteration(e4(), 50));
+    time.update(&iteration(e4(), -50));
+    assert_eq!(time.soft_limit(), planned.mul_f64(1.5));
+    time.update(&iteration(e4(), -400));
+    assert_eq!(time.soft_limit(), planned * 2);
+
+    // A rising score gives the time back
+    time.update(&iteration(e4(), 0));
+    assert_eq!(time.soft_limit(), planned);
+}
+
+#[test]
+fn test_clear_best_move_stops_early() {
+    let mut time = TimeManager::new(clock(60_000, 0, None));
+    let planned = time.soft_limit();
+    for _ in 0..5 {
+        time.update(&iteration(e4(), 30));
+        assert_eq!(time.soft_limit(), planned);
+    }
+    time.update(&iteration(e4(), 30));
+    assert_eq!(time.soft_limit(), planned / 2);
+}
+
+#[test]
+fn test_search_follows_the_clock() {
+    // Only one legal move: played after the first iteration despite a long clock
+    let mut board = Board::from_fen("7k/8/5K2/8/8/8/8/6R1 b - - 0 1").unwrap();
+    let start = Instant::now();
+    let result = board.search(SearchLimits::clock(clock(600_000, 0, None)));
+    assert_eq!(result.depth, 1);
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    let mut board = Board::initial();
+    let start = Instant::now();
+    let result = board.search(SearchLimits::clock(clock(3_000, 0, None)));
+    assert!(result.best_move.is_some());
+    assert!(start.elapsed() < Duration::from_millis(3_000 * 4 / 5 + 200));
+}
@@ -0,0 +1,136 @@
+use checkbit::board::Board;
+use checkbit::moves::{Move, MoveKind};
+use checkbit::search::{mate_in, mated_in, SearchLimits, Searcher};
+use checkbit::tt::{score_from_tt, score_to_tt, Bound, TranspositionTable};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn test_store_and_probe() {
+    let tt = TranspositionTable::new(1);
+    let mv = Move::new(12, 28, MoveKind::DoublePawnPush);
+    assert_eq!(tt.probe(42, 0), None);
+
+    tt.store(42, 7, Bound::Lower, -135, Some(mv), 0);
+    let entry = tt.probe(42, 0).unwrap();
+    assert_eq!(entry.best_move, Some(mv));
+    assert_eq!(entry.score, -135);
+    assert_eq!(entry.depth, 7);
+    assert_eq!(entry.bound, Bound::Lower);
+    assert_eq!(entry.age, tt.age());
+    assert_eq!(tt.probe(43, 0), None);
+}
+
+#[test]
+fn test_mate_scores_are_stored_relative_to_the_node() {
+    // Mate in 5 from the root found at ply 3 is mate in 2 from that node
+    assert_eq!(score_to_tt(mate_in(5), 3), mate_in(2));
+    assert_eq!(score_from_tt(mate_in(2), 3), mate_in(5));
+    assert_eq!(score_to_tt(mated_in(6), 2), mated_in(4));
+    assert_eq!(score_to_tt(250, 9), 250);
+
+    let tt = TranspositionTable::new(1);
+    tt.store(7, 4, Bound::Exact, mate_in(5), None, 3);
+    assert_eq!(tt.probe(7, 3).unwrap().score, mate_in(5));
+    assert_eq!(tt.probe(7, 1).unwrap().score, mate_in(3));
+    tt.store(8, 4, Bound::Exact, mated_in(6), None, 2);
+    assert_eq!(tt.probe(8, 4).unwrap().score, mated_in(8));
+}
+
+#[test]
+fn test_same_position_keeps_move_and_deeper_result() {
+    let tt = TranspositionTable::new(1);
+    let mv = Move::new(6, 21, MoveKind::Quiet);
+    tt.store(99, 10, Bound::Lower, 50, Some(mv), 0);
+
+    // Much shallower bounds do not overwrite a deep entry from the same search
+    tt.store(99, 2, Bound::Upper, -20, None, 0);
+    assert_eq!(tt.probe(99, 0).unwrap().depth, 10);
+
+    // Exact results do, keeping the known move
+    tt.store(99, 2, Bound::Exact, 30, None, 0);
+    let entry = tt.probe(99, 0).unwrap();
+    assert_eq!(entry.depth, 2);
+    assert_eq!(entry.best_move, Some(mv));
+}
+
+#[test]
+fn test_replacement_prefers_old_and_shallow_entries() {
+    // A one-bucket table, so every position competes for the same slots
+    let tt = TranspositionTable::new(0);
+    assert_eq!(tt.capacity(), 4);
+    for (hash, depth) in [(1, 9), (2, 3), (3, 8), (4, 7)] {
+        tt.store(hash, depth, Bound::Exact, 0, None, 0);
+    }
+    tt.store(5, 1, Bound::Exact, 0, None, 0);
+    assert_eq!(tt.probe(2, 0), None);
+    assert!(tt.probe(5, 0).is_some());
+
+    // After a new search the deepest entry is stale and goes first
+    tt.new_search();
+    for hash in [6, 7, 8] {
+        tt.store(hash, 1, Bound::Exact, 0, None, 0);
+    }
+    tt.store(9, 1, Bound::Exact, 0, None, 0);
+    assert_eq!(tt.probe(1, 0), None);
+}
+
+#[test]
+fn test_resize_clear_and_hashfull() {
+    let mut tt = TranspositionTable::new(1);
+    assert_eq!(tt.capacity(), 1024 * 1024 / 16);
+    assert_eq!(tt.hashfull(), 0);
+    for hash in 0..100_000u64 {
+        tt.store(
+            hash.wrapping_mul(0x9E37_79B9_7F4A_7C15),
+            1,
+            Bound::Exact,
+            0,
+            None,
+            0,
+        );
+    }
+    assert!(tt.hashfull() > 500);
+
+    tt.clear();
+    assert_eq!(tt.hashfull(), 0);
+
+    tt.resize(2);
+    assert_eq!(tt.capacity(), 2 * 1024 * 1024 / 16);
+}
+
+#[test]
+fn test_concurrent_access() {
+    let tt = Arc::new(TranspositionTable::new(1));
+    let handles: Vec<_> = (0..4u64)
+        .map(|thread| {
+            let tt = Arc::clone(&tt);
+            thread::spawn(move || {
+                for i in 0..10_000u64 {
+                    let hash = (thread << 32 | i).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                    tt.store(hash, (i % 64) as u8, Bound::Exact, i as i32 % 1000, None, 0);
+                    if let Some(entry) = tt.probe(hash, 0) {
+                        assert_eq!(entry.score, i as i32 % 1000);
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert!(tt.hashfull() > 0);
+}
+
+#[test]
+fn test_search_shares_table() {
+    let tt = Arc::new(TranspositionTable::new(4));
+    let mut board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    let mut searcher = Searcher::with_table(SearchLimits::depth(3), Arc::clone(&tt));
+    let result = searcher.search(&mut board);
+
+    let entry = tt.probe(board.hash(), 0).unwrap();
+    assert_eq!(entry.best_move, result.best_move);
+    assert_eq!(entry.score, mate_in(1));
+    assert_eq!(entry.bound, Bound::Exact);
+}
@@ -0,0 +1,222 @@
+use checkbit::board::Color;
+use checkbit::timeman::Clock;
+use checkbit::uci::GoCommand;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+// Runs the engine binary on a scripted session and returns its output lines
+fn session(script: &str) -> Vec<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_checkbit-uci"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+fn bestmove(lines: &[String]) -> &str {
+    let line = lines
+        .iter()
+        .find(|line| line.starts_with("bestmove"))
+        .expect("no bestmove");
+    line.split_whitespace().nth(1).unwrap()
+}
+
+#[test]
+fn test_handshake() {
+    let lines = session("uci\nisready\nquit\n");
+    assert_eq!(lines[0], "id name checkbit");
+    assert!(lines[1].starts_with("id author"));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("option name Hash type spin")));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("option name Threads type spin")));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("option name MultiPV type spin")));
+    let uciok = lines.iter().position(|line| line == "uciok").unwrap();
+    assert_eq!(lines[uciok + 1], "readyok");
+}
+
+#[test]
+fn test_go_depth_reports_every_iteration() {
+    let lines = session("position startpos moves e2e4 e7e5 g1f3\ngo depth 3\n");
+    let infos: Vec<&String> = lines
+        .iter()
+        .filter(|line| line.starts_with("info"))
+        .collect();
+    assert_eq!(infos.len(), 3);
+    for (depth, info) in infos.iter().enumerate() {
+        assert!(
+            info.starts_with(&format!("info depth {} ", depth + 1)),
+            "{}",
+            info
+        );
+        for field in [" score cp ", " nodes ", " nps ", " time ", " pv "] {
+            assert!(info.contains(field), "{} has no{}", info, field);
+        }
+    }
+    assert_eq!(
+        lines.last().unwrap().split_whitespace().next(),
+        Some("bestmove")
+    );
+}
+
+#[test]
+fn test_finds_mate_from_fen() {
+    let lines = session("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo depth 3\n");
+    assert_eq!(bestmove(&lines), "a1a8");
+    assert!(lines.iter().any(|line| line.contains("score mate 1")));
+}
+
+#[test]
+fn test_multi_pv() {
+    let lines = session("setoption name MultiPV value 3\nposition startpos\ngo depth 2\n");
+    for multipv in 1..=3 {
+        let field = format!(" multipv {} ", multipv);
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("info depth 2 ") && line.contains(&field)));
+    }
+    // Each line starts with a different move
+    let firsts: Vec<&str> = lines
+        .iter()
+        .filter(|line| line.starts_with("info depth 2 "))
+        .map(|line| {
+            line.split(" pv ")
+                .nth(1)
+                .unwrap()
+                .split(' ')
+                .next()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(firsts.len(), 3);
+    assert!(firsts[0] != firsts[1] && firsts[1] != firsts[2] && firsts[0] != firsts[2]);
+}
+
+#[test]
+fn test_threads() {
+    let lines = session("uci\n");
+    assert!(lines
+        .iter()
+        .any(|line| line == "option name Threads type spin default 1 min 1 max 1024"));
+
+    let lines = session(
+        "setoption name Threads value 4\nposition fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo depth 4\n",
+    );
+    assert_eq!(bestmove(&lines), "a1a8");
+}
+
+#[test]
+fn test_infinite_search_waits_for_stop() {
+    let lines = session("position startpos\ngo infinite\nisready\nstop\nquit\n");
+    assert!(lines.contains(&"readyok".to_string()));
+    assert_eq!(
+        lines.last().unwrap().split_whitespace().next(),
+        Some("bestmove")
+    );
+}
+
+#[test]
+fn test_clock_and_node_limits() {
+    let start = Instant::now();
+    let lines = session("position startpos\ngo wtime 3000 btime 3000 winc 0 binc 0 movestogo 30\n");
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert_ne!(bestmove(&lines), "0000");
+
+    let lines = session("position startpos\ngo nodes 2000\n");
+    assert_ne!(bestmove(&lines), "0000");
+}
+
+#[test]
+fn test_errors_and_terminal_positions() {
+    let lines = session("position startpos moves e2e5\nposition fen nonsense\nisready\n");
+    assert!(lines[0].starts_with("info string illegal move e2e5"));
+    assert!(lines[1].starts_with("info string invalid fen"));
+    assert_eq!(lines[2], "readyok");
+
+    // Checkmated side has no move to play
+    let lines = session("position fen R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1\ngo depth 2\n");
+    assert_eq!(bestmove(&lines), "0000");
+
+    let lines =
+        session("setoption name Hash value 1\nsetoption name Bogus value 3\nucinewgame\nisready\n");
+    assert_eq!(lines, vec!["info string unknown option Bogus", "readyok"]);
+}
+
+#[test]
+fn test_go_command_parsing() {
+    let go = GoCommand::parse(
+        &"wtime 60000 btime 30000 winc 1000 binc 500 movestogo 20"
+            .split(' ')
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(go.wtime, Some(Duration::from_secs(60)));
+    assert_eq!(go.binc, Some(Duration::from_millis(500)));
+    assert_eq!(go.movestogo, Some(20));
+    assert!(!go.infinite);
+
+    let white = go.limits(Color::White);
+    assert_eq!(white.time, None);
+    assert_eq!(
+        white.clock,
+        Some(Clock {
+            remaining: Duration::from_secs(60),
+            increment: Duration::from_secs(1),
+            moves_to_go: Some(20),
+        })
+    );
+    let black = go.limits(Color::Black).clock.unwrap();
+    assert_eq!(black.remaining, Duration::from_secs(30));
+    assert_eq!(black.increment, Duration::from_millis(500));
+
+    let go = GoCommand::parse(&["infinite", "depth", "7", "nodes", "500"]);
+    assert!(go.infinite);
+    assert_eq!(go.depth, Some(7));
+    assert_eq!(go.limits(Color::White).time, None);
+    assert_eq!(go.limits(Color::White).clock, None);
+    assert_eq!(go.limits(Color::White).nodes, Some(500));
+
+    let go = GoCommand::parse(&["movetime", "250"]);
+    assert_eq!(
+        go.limits(Color::Black).time,
+        Some(Duration::from_millis(250))
+    );
+}
+
+#[test]
+fn test_chess960_castling_notation() {
+    let lines = session("uci\n");
+    assert!(lines
+        .iter()
+        .any(|line| line == "option name UCI_Chess960 type check default false"));
+
+    // Castling is the only mate in both positions
+    let fen = "8/8/8/8/8/1P1k1P2/1P3P2/R3K1N1 w Q - 0 1";
+    let lines = session(&format!("position fen {}\ngo depth 2\n", fen));
+    assert_eq!(bestmove(&lines), "e1c1");
+    let lines = session(&format!(
+        "setoption name UCI_Chess960 value true\nposition fen {}\ngo depth 2\n",
+        fen
+    ));
+    assert_eq!(bestmove(&lines), "e1a1");
+    assert!(lines.iter().any(|line| line.ends_with(" pv e1a1")));
+    let lines = session("position fen 2rkr3/2p1p3/8/8/8/8/8/RK6 w Q - 0 1\ngo depth 2\n");
+    assert_eq!(bestmove(&lines), "b1a1");
+}
@@ -0,0 +1,91 @@
+use checkbit::board::{Board, Color, PieceType, START_FEN};
+use checkbit::moves::MoveList;
+use checkbit::variant::{variant_by_name, Outcome, Standard, Variant};
+
+// A variant defined outside the crate: pawns may not move, and bare kings win for the
+// side that still has other pieces
+#[derive(Debug)]
+struct FrozenPawns;
+
+impl Variant for FrozenPawns {
+    fn name(&self) -> &'static str {
+        "frozenpawns"
+    }
+
+    fn start_fen(&self) -> &'static str {
+        "2bqkb2/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1"
+    }
+
+    fn generate_moves(&self, board: &Board, list: &mut MoveList) {
+        let mut moves = MoveList::new();
+        board.generate_standard_moves(&mut moves);
+        let pawns = board.get_pieces(PieceType::Pawn, board.side_to_move());
+        for &mv in moves.iter() {
+            if !pawns.test_bit(mv.from()) {
+                list.push(mv);
+            }
+        }
+    }
+
+    fn outcome(&self, board: &Board) -> Option<Outcome> {
+        let mut moves = MoveList::new();
+        self.generate_moves(board, &mut moves);
+        if moves.is_empty() {
+            return Some(Outcome::Win(board.side_to_move().opposite()));
+        }
+        board.standard_outcome()
+    }
+}
+
+static FROZEN_PAWNS: FrozenPawns = FrozenPawns;
+
+#[test]
+fn test_standard_is_default() {
+    let board = Board::initial();
+    assert_eq!(board.variant().name(), "standard");
+    assert_eq!(Board::variant_start(&Standard).to_fen(), START_FEN);
+    assert_eq!(variant_by_name("Standard").unwrap().name(), "standard");
+    assert!(variant_by_name("nonexistent").is_none());
+}
+
+#[test]
+fn test_standard_outcomes() {
+    assert_eq!(Board::initial().outcome(), None);
+
+    // Fool's mate
+    let board =
+        Board::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::Black)));
+
+    // Stalemate, bare kings and the fifty-move rule
+    let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::Draw));
+    let board = Board::from_fen("7k/8/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::Draw));
+    let board = Board::from_fen("7k/8/6K1/8/8/8/8/R7 b - - 100 80").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::Draw));
+    let board = Board::from_fen("7k/8/6K1/8/8/8/8/R7 b - - 99 80").unwrap();
+    assert_eq!(board.outcome(), None);
+}
+
+#[test]
+fn test_custom_variant_hooks() {
+    let mut board = Board::variant_start(&FROZEN_PAWNS);
+    assert_eq!(board.variant().name(), "frozenpawns");
+
+    // Only the knights can move at the start
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    assert_eq!(moves.len(), 4);
+    assert_eq!(board.perft(1), 4);
+
+    // Black's pieces are walled in by their own pawns and cannot move
+    let mv = moves[0];
+    board.make_move(mv);
+    assert_eq!(board.outcome(), Some(Outcome::Win(Color::White)));
+    board.unmake_move(mv);
+
+    // Switching back to standard rules keeps the position
+    board.set_variant(&Standard);
+    assert_eq!(board.perft(1), 20);
+}
@@ -0,0 +1,238 @@
+use checkbit::board::Board;
+use checkbit::search::{mate_in, mated_in, SearchResult};
+use checkbit::xboard::{thinking_line, xboard_variant, TimeControl};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+// Runs the engine binary on a scripted session and returns its output lines
+fn session(script: &str) -> Vec<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_checkbit-xboard"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+fn moves(lines: &[String]) -> Vec<&str> {
+    lines
+        .iter()
+        .filter_map(|line| line.strip_prefix("move "))
+        .collect()
+}
+
+#[test]
+fn test_feature_negotiation() {
+    let lines = session("xboard\nprotover 2\nping 7\n");
+    assert!(lines[0].starts_with("feature myname=\"checkbit\""));
+    for feature in [
+        "setboard=1",
+        "usermove=1",
+        "ping=1",
+        "smp=1",
+        "variants=\"normal,",
+    ] {
+        assert!(lines[0].contains(feature), "missing {}", feature);
+    }
+    assert!(lines[0].contains("crazyhouse") && lines[0].contains("3check"));
+    assert!(lines[0].contains("fischerandom"));
+    assert_eq!(lines[1], "feature done=1");
+    assert_eq!(lines[2], "pong 7");
+}
+
+#[test]
+fn test_cores() {
+    let lines = session("new\ncores 4\nsd 3\nusermove e2e4\n");
+    assert_eq!(moves(&lines).len(), 1);
+}
+
+#[test]
+fn test_engine_replies_to_user_moves() {
+    let lines = session("new\nsd 2\nusermove e2e4\n");
+    assert_eq!(moves(&lines).len(), 1);
+
+    // Bare moves are accepted too, and the reply must be a legal black move
+    let lines = session("new\nsd 2\ne2e4\n");
+    let reply = moves(&lines)[0];
+    assert!(reply.starts_with(|file: char| ('a'..='h').contains(&file)));
+    assert!(["7", "8"].contains(&&reply[1..2]), "{}", reply);
+}
+
+#[test]
+fn test_force_and_go() {
+    let lines = session("new\nforce\nusermove e2e4\nusermove e7e5\nsd 2\ngo\n");
+    assert_eq!(moves(&lines).len(), 1);
+
+    // After go the engine keeps playing the same side
+    let lines = session("new\nforce\nsd 1\ngo\nusermove e7e5\n");
+    assert_eq!(moves(&lines).len(), 2);
+
+    // playother makes the engine take the side not to move
+    let lines = session("new\nforce\nsd 1\nplayother\nusermove e2e4\n");
+    assert_eq!(moves(&lines).len(), 1);
+}
+
+#[test]
+fn test_setboard_and_result_claims() {
+    let lines = session("new\nforce\nsetboard 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\nsd 3\ngo\n");
+    assert_eq!(moves(&lines), vec!["a1a8"]);
+    assert_eq!(lines.last().unwrap(), "1-0 {White wins}");
+
+    let lines = session("new\nsetboard not a fen\n");
+    assert_eq!(lines, vec!["tellusererror Illegal position"]);
+
+    // A finished game is claimed instead of searched
+    let lines = session("new\nforce\nsetboard R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1\ngo\n");
+    assert_eq!(lines, vec!["1-0 {White wins}"]);
+}
+
+#[test]
+fn test_undo_and_remove() {
+    let lines = session(
+        "new\nforce\nusermove e2e4\nusermove e7e5\nundo\nusermove e7e5\n\
+         remove\nusermove e2e4\nusermove e2e4\n",
+    );
+    assert_eq!(lines, vec!["Illegal move: e2e4"]);
+}
+
+#[test]
+fn test_illegal_moves_and_unknown_commands() {
+    let lines = session("new\nforce\nusermove e2e5\nfrobnicate\n");
+    assert_eq!(lines[0], "Illegal move: e2e5");
+    assert!(lines[1].starts_with("Error (unknown command): frobnicate"));
+
+    // Unknown commands are answered while the engine is still thinking
+    let lines = session("new\nst 1\nusermove e2e4\nhint\n");
+    let error = lines
+        .iter()
+        .position(|line| line.starts_with("Error (unknown command): hint"))
+        .unwrap();
+    let reply = lines
+        .iter()
+        .position(|line| line.starts_with("move "))
+        .unwrap();
+    assert!(error < reply);
+}
+
+#[test]
+fn test_variants() {
+    let lines = session("new\nvariant crazyhouse\nforce\nusermove e2e4\nusermove d7d5\nusermove e4d5\nusermove Q@e4\n");
+    assert_eq!(lines, vec!["Illegal move: Q@e4"]);
+    let lines = session("new\nvariant crazyhouse\nforce\nusermove e2e4\nusermove d7d5\nusermove e4d5\nusermove d8d5\nusermove P@e4\n");
+    assert!(lines.is_empty(), "{:?}", lines);
+
+    let lines = session("new\nvariant shogi\n");
+    assert!(lines[0].starts_with("Error (unsupported variant)"));
+    assert_eq!(xboard_variant("giveaway").unwrap().name(), "antichess");
+    assert_eq!(xboard_variant("normal").unwrap().name(), "standard");
+}
+
+#[test]
+fn test_fischer_random_castling() {
+    // Castling is the only mate
+    let lines = session(
+        "new\nvariant fischerandom\nforce\nsetboard 8/8/8/8/8/1P1k1P2/1P3P2/R3K1N1 w Q - 0 1\nsd 2\ngo\n",
+    );
+    assert_eq!(moves(&lines), vec!["O-O-O"]);
+    assert_eq!(lines.last().unwrap(), "1-0 {White wins}");
+
+    // Castles are read as O-O-O or as the king taking its own rook
+    let lines = session(
+        "new\nvariant fischerandom\nforce\nsetboard 2rkr3/2p1p3/8/8/8/8/8/RK6 w Q - 0 1\nO-O-O\nundo\nusermove b1a1\nundo\nusermove b1c1\nundo\nO-O\n",
+    );
+    assert_eq!(lines, vec!["Illegal move: O-O"]);
+    // The castle mates, so the engine claims the win instead of replying
+    let lines = session(
+        "new\nvariant fischerandom\nsetboard 2rkr3/2p1p3/8/8/8/8/8/RK6 w Q - 0 1\nsd 2\nusermove b1a1\n",
+    );
+    assert_eq!(lines, vec!["1-0 {White wins}"]);
+}
+
+#[test]
+fn test_time_controls() {
+    let start = Instant::now();
+    let lines = session("new\nlevel 40 0:10 0\ntime 1000\notim 1000\nusermove e2e4\n");
+    assert_eq!(moves(&lines).len(), 1);
+    assert!(start.elapsed() < Duration::from_secs(3));
+
+    let start = Instant::now();
+    let lines = session("new\nst 0.2\nusermove d2d4\n");
+    assert_eq!(moves(&lines).len(), 1);
+    assert!(start.elapsed() < Duration::from_secs(3));
+}
+
+#[test]
+fn test_interrupting_the_engine() {
+    // Move now plays the best move found so far
+    let start = Instant::now();
+    let lines = session("new\nst 30\nusermove e2e4\n?\nquit\n");
+    assert_eq!(moves(&lines).len(), 1);
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    // A result ends the game without a move
+    let start = Instant::now();
+    let lines = session("new\nst 30\nusermove e2e4\nresult 1-0 {White resigns}\nquit\n");
+    assert!(moves(&lines).is_empty());
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn test_thinking_output() {
+    let lines = session("new\npost\nsd 3\nusermove e2e4\n");
+    let thinking: Vec<&String> = lines
+        .iter()
+        .filter(|line| !line.starts_with("move"))
+        .collect();
+    assert_eq!(thinking.len(), 3);
+    for (depth, line) in thinking.iter().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        assert_eq!(fields[0], (depth + 1).to_string());
+        assert!(fields[1].parse::<i32>().is_ok());
+        assert!(fields.len() >= 5);
+    }
+
+    let mut result = SearchResult {
+        depth: 5,
+        score: mate_in(3),
+        nodes: 1234,
+        time: Duration::from_millis(1500),
+        ..Default::default()
+    };
+    assert_eq!(
+        thinking_line(&Board::initial(), &result),
+        "5 100002 150 1234"
+    );
+    result.score = mated_in(4);
+    assert_eq!(
+        thinking_line(&Board::initial(), &result),
+        "5 -100002 150 1234"
+    );
+}
+
+#[test]
+fn test_level_parsing() {
+    let control = TimeControl::parse_level(&["40", "5", "0"]).unwrap();
+    assert_eq!(control.moves_per_session, 40);
+    assert_eq!(control.base, Duration::from_secs(300));
+    assert_eq!(control.increment, Duration::ZERO);
+
+    let control = TimeControl::parse_level(&["0", "2:30", "1.5"]).unwrap();
+    assert_eq!(control.base, Duration::from_secs(150));
+    assert_eq!(control.increment, Duration::from_millis(1500));
+
+    assert_eq!(TimeControl::parse_level(&["40", "5"]), None);
+    assert_eq!(TimeControl::parse_level(&["x", "5", "0"]), None);
+}
@@ -0,0 +1,110 @@
+use checkbit::board::{Board, PieceType};
+use checkbit::moves::{Move, MoveKind, MoveList};
+use checkbit::variant::{Crazyhouse, ThreeCheck};
+
+fn play(board: &mut Board, uci: &str) -> Move {
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    let mv = *moves
+        .iter()
+        .find(|mv| mv.to_string() == uci)
+        .unwrap_or_else(|| panic!("{} is not legal", uci));
+    board.make_move(mv);
+    mv
+}
+
+// Walks every line to the given depth, checking the incremental hash against a board
+// rebuilt from FEN and that unmaking restores it
+fn check_hashes(board: &mut Board, depth: u32) {
+    let fresh = Board::from_fen(&board.to_fen()).unwrap();
+    assert_eq!(board.hash(), fresh.hash(), "{}", board.to_fen());
+    if depth == 0 {
+        return;
+    }
+    let before = board.hash();
+    let mut moves = MoveList::new();
+    board.generate_moves(&mut moves);
+    for &mv in moves.iter() {
+        board.make_move(mv);
+        assert_ne!(board.hash(), before, "{} did not change the hash", mv);
+        check_hashes(board, depth - 1);
+        board.unmake_move(mv);
+        assert_eq!(board.hash(), before, "{} was not undone", mv);
+    }
+}
+
+#[test]
+fn test_hash_is_incremental() {
+    check_hashes(&mut Board::initial(), 3);
+    let mut kiwipete =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    check_hashes(&mut kiwipete, 2);
+}
+
+#[test]
+fn test_transpositions_hash_equal() {
+    let mut a = Board::initial();
+    for uci in ["g1f3", "g8f6", "b1c3", "b8c6"] {
+        play(&mut a, uci);
+    }
+    let mut b = Board::initial();
+    for uci in ["b1c3", "b8c6", "g1f3", "g8f6"] {
+        play(&mut b, uci);
+    }
+    assert_eq!(a.hash(), b.hash());
+    assert_ne!(a.hash(), Board::initial().hash());
+}
+
+#[test]
+fn test_hash_covers_side_castling_and_en_passant() {
+    let white = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    let black = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+    let no_castling = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1").unwrap();
+    assert_ne!(white.hash(), black.hash());
+    assert_ne!(white.hash(), no_castling.hash());
+
+    let en_passant = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    let no_en_passant = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_ne!(en_passant.hash(), no_en_passant.hash());
+}
+
+#[test]
+fn test_hash_ignores_clocks() {
+    let a = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    let b = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 37 60").unwrap();
+    assert_eq!(a.hash(), b.hash());
+}
+
+#[test]
+fn test_hash_covers_pockets_and_checks() {
+    let empty = Board::from_variant_fen("4k3/8/8/8/8/8/8/4K3[] w - - 0 1", &Crazyhouse).unwrap();
+    let knight = Board::from_variant_fen("4k3/8/8/8/8/8/8/4K3[N] w - - 0 1", &Crazyhouse).unwrap();
+    assert_ne!(empty.hash(), knight.hash());
+    let mut drop = knight.clone();
+    let mv = play(&mut drop, "N@d4");
+    assert_eq!(mv.kind(), MoveKind::Drop(PieceType::Knight));
+    assert_ne!(drop.hash(), knight.hash());
+    drop.unmake_move(mv);
+    assert_eq!(drop.hash(), knight.hash());
+
+    let none = Board::from_variant_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1 +0+0", &ThreeCheck).unwrap();
+    let one = Board::from_variant_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1 +1+0", &ThreeCheck).unwrap();
+    assert_ne!(none.hash(), one.hash());
+}
+
+#[test]
+fn test_move_bits_round_trip() {
+    let mut moves = MoveList::new();
+    Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+        .unwrap()
+        .generate_moves(&mut moves);
+    Board::from_fen("1r5k/P7/8/8/8/8/8/4K3 w - - 0 1")
+        .unwrap()
+        .generate_moves(&mut moves);
+    for &mv in moves.iter() {
+        assert!(mv.to_bits() < 1 << 17);
+        assert_eq!(Move::from_bits(mv.to_bits()), Some(mv));
+    }
+    assert_eq!(Move::from_bits(31 << 12), None);
+}